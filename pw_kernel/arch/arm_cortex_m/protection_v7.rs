@@ -14,14 +14,17 @@
 
 //! PMSAv7 (ARMv7-M) MPU implementation
 
+use core::sync::atomic::{AtomicPtr, Ordering};
+
 use kernel_config::{CortexMKernelConfigInterface as _, KernelConfig};
 use memory_config::{MemoryRegion, MemoryRegionType};
+use pmsav7::AccessPermission;
 
 use crate::regs::Regs;
 use crate::regs::mpu::*;
 
 /// PMSAv7 MPU Region
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Eq)]
 pub struct MpuRegion {
     #[allow(dead_code)]
     pub rbar: RbarVal,
@@ -29,13 +32,6 @@ pub struct MpuRegion {
     pub rasr: RasrVal,
 }
 
-/// Helper structure for PMSAv7 aligned region calculation
-struct AlignedRegion {
-    base: usize,
-    size_field: u8,
-    srd_mask: u8,
-}
-
 impl MpuRegion {
     pub const fn const_default() -> Self {
         Self {
@@ -44,194 +40,41 @@ impl MpuRegion {
         }
     }
 
-    pub const fn from_memory_region(region: &MemoryRegion) -> Self {
-        // PMSAv7 requires power-of-2 sized regions aligned to their size.
-        // Use sub-regions to handle arbitrary ranges.
-        let aligned_region = Self::calculate_aligned_region(region.start, region.end);
-        
-        let (xn, tex, s, c, b, ap) = match region.ty {
-            MemoryRegionType::ReadOnlyData => (
-                /* xn */ true,
-                /* tex */ 0b001,  // Normal memory, outer and inner write-back
-                /* s */ true, /* c */ true, /* b */ true,
-                RasrAp::RoAny,
-            ),
-            MemoryRegionType::ReadWriteData => (
-                /* xn */ true,
-                /* tex */ 0b001,  // Normal memory, outer and inner write-back
-                /* s */ false, /* c */ true, /* b */ true,
-                RasrAp::RwAny,
-            ),
-            MemoryRegionType::ReadOnlyExecutable => (
-                /* xn */ false,
-                /* tex */ 0b001,  // Normal memory, outer and inner write-back
-                /* s */ true, /* c */ true, /* b */ true,
-                RasrAp::RoAny,
-            ),
-            MemoryRegionType::ReadWriteExecutable => (
-                /* xn */ false,
-                /* tex */ 0b001,  // Normal memory, outer and inner write-back
-                /* s */ true, /* c */ true, /* b */ true,
-                RasrAp::RwAny,
-            ),
-            MemoryRegionType::Device => (
-                /* xn */ true,
-                /* tex */ 0b000,  // Device memory
-                /* s */ true, /* c */ false, /* b */ true,
-                RasrAp::RoAny,
-            ),
+    /// Build the descriptor for a single naturally-aligned power-of-two block.
+    ///
+    /// `base` is aligned to `size` (a power of two in `MIN_BLOCK_SIZE..=`
+    /// `MAX_BLOCK_SIZE`); the whole block is enabled (no sub-regions disabled),
+    /// because the [buddy decomposition](MemoryConfig::const_new) only ever
+    /// emits blocks that lie wholly inside the requested range.  The attribute
+    /// derivation is shared with the Cortex-R backend via [`pmsav7`]; this only
+    /// encodes it into the ARMv7-M `RBAR`/`RASR` layout.
+    #[expect(clippy::cast_possible_truncation)]
+    const fn block(base: u64, size: u64, ty: MemoryRegionType) -> Self {
+        // The block must be naturally aligned for the PMSAv7 `RBAR.ADDR`
+        // encoding (which ignores the low `log2(size)` address bits) to name
+        // the intended range.
+        assert!(base % size == 0, "PMSAv7 block base must be size-aligned");
+        let attrs = pmsav7::region_attributes(ty);
+        let ap = match attrs.ap {
+            AccessPermission::ReadOnly => RasrAp::RoAny,
+            AccessPermission::ReadWrite => RasrAp::RwAny,
         };
 
-        #[expect(clippy::cast_possible_truncation)]
         Self {
             rbar: RbarVal::const_default()
                 .with_valid(false)  // Region selected by RNR, not by RBAR.REGION
-                .with_addr(aligned_region.base as u32),
+                .with_addr(base as u32),
 
             rasr: RasrVal::const_default()
                 .with_enable(true)
-                .with_size(aligned_region.size_field)
-                .with_srd(aligned_region.srd_mask)
-                .with_tex(tex)
-                .with_s(s)
-                .with_c(c)
-                .with_b(b)
+                .with_size(pmsav7::size_field(size))
+                .with_srd(0)
+                .with_tex(attrs.tex)
+                .with_s(attrs.s)
+                .with_c(attrs.c)
+                .with_b(attrs.b)
                 .with_ap(ap)
-                .with_xn(xn),
-        }
-    }
-
-    /// Helper to calculate SIZE field from region size in bytes
-    const fn calculate_size_field(size_bytes: usize) -> u8 {
-        // SIZE = log2(size) - 1
-        // Find the position of the highest set bit
-        let mut size = size_bytes;
-        let mut bits = 0;
-        while size > 1 {
-            size >>= 1;
-            bits += 1;
-        }
-        // SIZE field is bits - 1, minimum is 4 (32 bytes)
-        if bits < 5 {
-            4  // Minimum 32 bytes
-        } else {
-            #[expect(clippy::cast_possible_truncation)]
-            ((bits - 1) as u8)
-        }
-    }
-
-    /// Calculate an aligned region that covers [start, end) using sub-regions
-    const fn calculate_aligned_region(start: usize, end: usize) -> AlignedRegion {
-        let requested_size = end - start;
-        
-        // PMSAv7 maximum region size is 4GB (2^32), but SIZE field max is 31 (2^32)
-        // For very large regions (like kernel's full address space), use maximum size
-        const MAX_REGION_SIZE: usize = 0x8000_0000; // 2GB, SIZE=30
-        
-        if requested_size >= MAX_REGION_SIZE {
-            // Use maximum region size with no sub-regions disabled
-            return AlignedRegion {
-                base: 0,
-                size_field: 30, // 2GB = 2^31, SIZE = 31-1 = 30
-                srd_mask: 0,
-            };
-        }
-        
-        // Find the smallest power-of-2 region that can cover the requested range
-        // Start with the requested size, round up to next power of 2
-        let mut region_size = 32; // Minimum 32 bytes
-        while region_size < requested_size {
-            region_size *= 2;
-            if region_size > MAX_REGION_SIZE {
-                // Fall back to max size
-                return AlignedRegion {
-                    base: 0,
-                    size_field: 30,
-                    srd_mask: 0,
-                };
-            }
-        }
-        
-        // Find an aligned base that covers the requested range
-        // The base must be aligned to the region size
-        // 
-        // CRITICAL: We must not align down across major memory boundaries.
-        // On AST1030: Flash ends at 0x3FFFF, RAM starts at 0x40000.
-        // If we blindly align down, a region starting at 0x60420 (in RAM) could
-        // align to 0x40000 or even 0x00000, causing it to overlap with flash.
-        //
-        // Strategy: Try aligning down first, but if that crosses the start of
-        // the current 256KB page (which typically separates flash/RAM), align
-        // to the page boundary instead. This prevents cross-boundary issues while
-        // still allowing efficient region packing within the same memory type.
-        const PAGE_256KB: usize = 0x40000;
-        let start_page = start & !(PAGE_256KB - 1);
-        
-        let naive_aligned_base = start & !(region_size - 1); // Align down to region_size
-        
-        // If alignment crosses below the start's 256KB page boundary, use the page boundary instead
-        let aligned_base = if naive_aligned_base < start_page {
-            start_page
-        } else {
-            naive_aligned_base
-        };
-        
-        // Debug logging to trace alignment decisions
-        // This is const fn so we can't use pw_log, but the values will be visible in MPU dumps
-        
-        // Check if this aligned region covers the end address
-        // If not, we need a larger region
-        let mut final_base = aligned_base;
-        let mut final_size = region_size;
-        
-        while final_base + final_size < end {
-            final_size *= 2;
-            let candidate_base = start & !(final_size - 1);
-            
-            // Apply the same page boundary constraint
-            final_base = if candidate_base < start_page {
-                start_page
-            } else {
-                candidate_base
-            };
-            
-            if final_size > MAX_REGION_SIZE {
-                // Fall back to max size at base 0
-                return AlignedRegion {
-                    base: 0,
-                    size_field: 30,
-                    srd_mask: 0,
-                };
-            }
-        }
-        
-        // Calculate SIZE field: log2(region_size) - 1
-        let size_field = Self::calculate_size_field(final_size);
-        
-        // Calculate sub-region disable mask
-        // Each sub-region is region_size / 8
-        let subregion_size = final_size / 8;
-        let mut srd_mask: u8 = 0;
-        
-        // Disable sub-regions that fall outside [start, end)
-        let mut i = 0;
-        while i < 8 {
-            let subregion_start = final_base + i * subregion_size;
-            let subregion_end = subregion_start + subregion_size;
-            
-            // Disable if this sub-region doesn't overlap with [start, end)
-            // A sub-region overlaps if: subregion_start < end AND subregion_end > start
-            let overlaps = subregion_start < end && subregion_end > start;
-            if !overlaps {
-                srd_mask |= 1 << i;
-            }
-            i += 1;
-        }
-        
-        AlignedRegion {
-            base: final_base,
-            size_field,
-            srd_mask,
+                .with_xn(attrs.xn),
         }
     }
 }
@@ -244,19 +87,50 @@ pub struct MemoryConfig {
 }
 
 impl MemoryConfig {
-    /// Create a new `MemoryConfig` in a `const` context
+    /// Create a new `MemoryConfig` in a `const` context.
+    ///
+    /// Each [`MemoryRegion`] `[start, end)` is decomposed into a set of
+    /// naturally-aligned power-of-two PMSAv7 entries (a buddy decomposition):
+    /// repeatedly emit the largest block `S` such that `start` is aligned to
+    /// `S` and `start + S <= end`, then advance `start`.  This covers the range
+    /// exactly — no rounding up to a single oversized block, and no
+    /// cross-boundary alignment hacks — at the cost of O(log range) entries per
+    /// region.
     ///
     /// # Panics
-    /// Will panic if the current target's MPU does not support enough regions
-    /// to represent `regions`.
+    /// Panics at compile time if the decomposed entries do not fit in the
+    /// target's `KernelConfig::NUM_MPU_REGIONS` MPU regions, rather than
+    /// silently collapsing to a permissive whole-address-space region.
     #[must_use]
     pub const fn const_new(regions: &'static [MemoryRegion]) -> Self {
         let mut mpu_regions = [MpuRegion::const_default(); KernelConfig::NUM_MPU_REGIONS];
+        let mut count = 0;
+
         let mut i = 0;
         while i < regions.len() {
-            mpu_regions[i] = MpuRegion::from_memory_region(&regions[i]);
+            let region = &regions[i];
+            let mut base = region.start as u64;
+            // The top of the 32-bit space is expressed as `end == usize::MAX`;
+            // `normalized_end` maps it to one-past-the-end so a full-space
+            // region collapses to a single 4 GiB block rather than an unbounded
+            // chain.
+            let end = pmsav7::normalized_end(region.end);
+            // Reject ranges that are not 32-byte granular up front; otherwise
+            // the decomposition below would map up to 31 bytes past `end`.
+            pmsav7::assert_block_granular(base, end);
+
+            while base < end {
+                let size = pmsav7::largest_block(base, end);
+                if count >= KernelConfig::NUM_MPU_REGIONS {
+                    panic!("MemoryConfig does not fit in the target's MPU regions");
+                }
+                mpu_regions[count] = MpuRegion::block(base, size, region.ty);
+                count += 1;
+                base += size;
+            }
             i += 1;
         }
+
         Self {
             mpu_regions,
             generic_regions: regions,
@@ -314,8 +188,278 @@ impl MemoryConfig {
         unsafe {
             core::arch::asm!("dsb", "isb", options(nostack, preserves_flags));
         }
+
+        // On cache-equipped cores (Cortex-M7) a region whose cacheability
+        // changed since the previous config can otherwise observe stale lines,
+        // since the barriers above only order the MPU writes.  Clean and
+        // invalidate each range whose normal/Device attribute transitioned.
+        if KernelConfig::HAS_DATA_CACHE {
+            let prev = ACTIVE_CONFIG.load(Ordering::Acquire);
+            if !prev.is_null() {
+                // SAFETY: `write` only ever stores a `'static` config pointer.
+                let prev = unsafe { &*prev };
+                for region in self.generic_regions {
+                    let transitioned = match prev.region_for(region.start) {
+                        Some((_, p)) => is_cacheable(p.ty) != is_cacheable(region.ty),
+                        None => true,
+                    };
+                    if transitioned {
+                        let len = region.end - region.start;
+                        // SAFETY: cleaning then invalidating a config range is
+                        // sound; the range names memory this config maps.
+                        unsafe {
+                            cache::clean_dcache_by_range(region.start, len);
+                            cache::invalidate_dcache_by_range(region.start, len);
+                        }
+                    }
+                }
+                // SAFETY: paired barriers issued inside the call.
+                unsafe { cache::invalidate_icache_all() };
+            }
+        }
+
+        // Record the now-active config so the MemManage handler can map a
+        // faulting address back to one of its `generic_regions`.
+        ACTIVE_CONFIG.store(core::ptr::from_ref(self).cast_mut(), Ordering::Release);
+    }
+
+    /// Reprogram only the MPU slots that differ from `prev`, the config
+    /// currently loaded in hardware.
+    ///
+    /// On the context-switch hot path most regions — the kernel and shared
+    /// mappings at the front of the array — are identical between threads; only
+    /// the dynamic per-thread regions at the tail change.  `write` rewrites all
+    /// `NUM_MPU_REGIONS` triples unconditionally, which is pure overhead for the
+    /// static slots; this diffs `self` against `prev` and touches only the RNR
+    /// slots whose `RbarVal`/`RasrVal` actually changed, skipping the `dsb`/`isb`
+    /// entirely when nothing did.
+    ///
+    /// # Safety
+    /// `prev` must describe the configuration currently programmed in the MPU
+    /// (normally the outgoing thread's config); otherwise stale slots are left
+    /// in place.  Same soundness obligations as [`write`](Self::write).
+    pub unsafe fn write_delta(&self, prev: &Self) {
+        let mut mpu = Regs::get().mpu;
+        let mut changed = false;
+
+        for (index, (region, old)) in self
+            .mpu_regions
+            .iter()
+            .zip(prev.mpu_regions.iter())
+            .enumerate()
+        {
+            if region == old {
+                continue;
+            }
+            pw_assert::debug_assert!(index < 255);
+            #[expect(clippy::cast_possible_truncation)]
+            {
+                mpu.rnr.write(RnrVal::default().with_region(index as u8));
+            }
+            mpu.rbar.write(region.rbar);
+            mpu.rasr.write(region.rasr);
+            changed = true;
+        }
+
+        // Nothing moved: the pipeline is already coherent with the MPU, so skip
+        // the barriers that make `write` expensive.
+        if !changed {
+            return;
+        }
+
+        // SAFETY: same barrier requirement as `write` after touching the MPU.
+        unsafe {
+            core::arch::asm!("dsb", "isb", options(nostack, preserves_flags));
+        }
+
+        ACTIVE_CONFIG.store(core::ptr::from_ref(self).cast_mut(), Ordering::Release);
+    }
+
+}
+
+/// D-cache/I-cache maintenance by memory address, for cache-equipped PMSAv7
+/// cores such as the Cortex-M7.
+///
+/// The operations iterate the affected address range in 32-byte cache-line
+/// steps, writing each line's address to the relevant SCB maintenance register
+/// — the same clean/invalidate-by-MVA technique the Cortex-A cache code uses —
+/// and finish with the barriers the architecture requires for the effect to be
+/// observable.  On cores without a data cache these are never reached (see the
+/// `HAS_DATA_CACHE` guard in [`MemoryConfig::write`]).
+pub mod cache {
+    /// Cortex-M7 cache line size in bytes.
+    const LINE: usize = 0x20;
+
+    /// SCB `DCCMVAC`: clean D-cache line by address.
+    const DCCMVAC: *mut u32 = 0xE000_EF68 as *mut u32;
+    /// SCB `DCIMVAC`: invalidate D-cache line by address.
+    const DCIMVAC: *mut u32 = 0xE000_EF5C as *mut u32;
+    /// SCB `ICIALLU`: invalidate entire I-cache.
+    const ICIALLU: *mut u32 = 0xE000_EF50 as *mut u32;
+
+    /// Clean (write back) the D-cache lines covering `[addr, addr + len)`.
+    ///
+    /// # Safety
+    /// The range must name memory it is safe to write back to the next level.
+    pub unsafe fn clean_dcache_by_range(addr: usize, len: usize) {
+        // SAFETY: the SCB maintenance registers are always mapped; each write
+        // acts on the line containing the supplied address.
+        unsafe { maintain(DCCMVAC, addr, len) };
+    }
+
+    /// Invalidate the D-cache lines covering `[addr, addr + len)`.
+    ///
+    /// # Safety
+    /// Discards any dirty lines in the range; the caller must have cleaned them
+    /// first if their contents matter.
+    pub unsafe fn invalidate_dcache_by_range(addr: usize, len: usize) {
+        // SAFETY: see [`clean_dcache_by_range`].
+        unsafe { maintain(DCIMVAC, addr, len) };
+    }
+
+    /// Invalidate the entire instruction cache.
+    ///
+    /// # Safety
+    /// Must be paired with the `dsb; isb` issued here before the core fetches
+    /// from any range whose instructions changed.
+    pub unsafe fn invalidate_icache_all() {
+        // SAFETY: ICIALLU ignores its written value; the barriers make the
+        // invalidation visible to subsequent instruction fetches.
+        unsafe {
+            ICIALLU.write_volatile(0);
+            core::arch::asm!("dsb", "isb", options(nostack, preserves_flags));
+        }
+    }
+
+    /// Walk `[addr, addr + len)` in cache-line steps, poking `reg` with each
+    /// line address, then `dsb`.
+    ///
+    /// # Safety
+    /// `reg` must be an SCB by-address maintenance register.
+    unsafe fn maintain(reg: *mut u32, addr: usize, len: usize) {
+        let mut line = addr & !(LINE - 1);
+        let end = addr.saturating_add(len);
+        // SAFETY: forwarded to the caller; each write targets one cache line.
+        unsafe {
+            while line < end {
+                #[expect(clippy::cast_possible_truncation)]
+                reg.write_volatile(line as u32);
+                line += LINE;
+            }
+            core::arch::asm!("dsb", options(nostack, preserves_flags));
+        }
+    }
+}
+
+/// Whether `ty` maps to cacheable normal memory (everything but Device).
+const fn is_cacheable(ty: MemoryRegionType) -> bool {
+    !matches!(ty, MemoryRegionType::Device)
+}
+
+/// The `MemoryConfig` most recently programmed by [`MemoryConfig::write`].
+///
+/// Read only by [`mem_manage_handler`] to describe the region a fault hit; the
+/// pointer is always either null or a `'static` config, so the handler never
+/// dereferences a dangling reference.
+static ACTIVE_CONFIG: AtomicPtr<MemoryConfig> = AtomicPtr::new(core::ptr::null_mut());
+
+/// Configurable System Control Block registers consulted by the fault decoder.
+/// These live outside the MPU block and are only ever read here, so we access
+/// them by address rather than widening [`Regs`].
+mod scb {
+    /// Configurable Fault Status Register; its low byte is the MMFSR.
+    pub const CFSR: *mut u32 = 0xE000_ED28 as *mut u32;
+    /// MemManage Fault Address Register.
+    pub const MMFAR: *const u32 = 0xE000_ED34 as *const u32;
+
+    pub const IACCVIOL: u32 = 1 << 0;
+    pub const DACCVIOL: u32 = 1 << 1;
+    pub const MUNSTKERR: u32 = 1 << 3;
+    pub const MSTKERR: u32 = 1 << 4;
+    pub const MLSPERR: u32 = 1 << 5;
+    pub const MMARVALID: u32 = 1 << 7;
+
+    /// Write-1-to-clear mask covering every MMFSR status bit.
+    pub const MMFSR_MASK: u32 = IACCVIOL | DACCVIOL | MUNSTKERR | MSTKERR | MLSPERR | MMARVALID;
+}
+
+impl MemoryConfig {
+    /// Index of the region in `generic_regions` containing `addr`, if any.
+    #[must_use]
+    fn region_for(&self, addr: usize) -> Option<(usize, &MemoryRegion)> {
+        self.generic_regions
+            .iter()
+            .enumerate()
+            .find(|(_, r)| addr >= r.start && addr < r.end)
+    }
+}
+
+/// MemManage fault handler: decode why PMSAv7 rejected the access and log it.
+///
+/// Without this, an MPU violation surfaces only as the opaque fault /
+/// context-switch loop the [`write`](MemoryConfig::write) barriers comment
+/// warns about.  Here we read the MMFSR byte of the CFSR and, when
+/// `MMARVALID` is set, the faulting address from MMFAR, then map that address
+/// back to the offending [`MemoryRegion`] (and its MPU index) by walking the
+/// active config's `generic_regions` — turning a silent hang into a line like
+/// `DACCVIOL at 0x00060420: no region`.
+///
+/// # Safety
+/// Installed as the MemManage vector; must only run in exception context.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mem_manage_handler() {
+    // SAFETY: the SCB fault-status registers are always mapped and readable in
+    // handler mode; we are the only reader and clear the bits we consume.
+    let mmfsr = unsafe { scb::CFSR.read_volatile() };
+
+    // Classify the access. MemManage distinguishes instruction vs data/stacking
+    // faults; `DACCVIOL`/stacking faults are data accesses, `IACCVIOL` is a
+    // fetch.
+    let access = if mmfsr & scb::IACCVIOL != 0 {
+        "IACCVIOL (execute)"
+    } else if mmfsr & scb::DACCVIOL != 0 {
+        "DACCVIOL (data)"
+    } else if mmfsr & (scb::MSTKERR | scb::MUNSTKERR | scb::MLSPERR) != 0 {
+        "stacking fault"
+    } else {
+        "MemManage"
+    };
+
+    if mmfsr & scb::MMARVALID != 0 {
+        // SAFETY: MMARVALID guarantees MMFAR holds the faulting address.
+        let addr = unsafe { scb::MMFAR.read_volatile() } as usize;
+
+        let cfg = ACTIVE_CONFIG.load(Ordering::Acquire);
+        let described = if cfg.is_null() {
+            false
+        } else {
+            // SAFETY: `write` only ever stores a `'static` config pointer.
+            match unsafe { &*cfg }.region_for(addr) {
+                Some((index, region)) => {
+                    pw_log::error!(
+                        "{} at {:#010x}: in region {} type {}",
+                        access,
+                        addr as u32,
+                        index as u32,
+                        region.ty as u32
+                    );
+                    true
+                }
+                None => false,
+            }
+        };
+        if !described {
+            pw_log::error!("{} at {:#010x}: no region", access, addr as u32);
+        }
+    } else {
+        pw_log::error!("{}: faulting address unknown", access);
     }
 
+    // Acknowledge the fault by clearing the MMFSR status bits (write-1-to-clear)
+    // so a subsequent fault is distinguishable from this one.
+    // SAFETY: CFSR is write-1-to-clear; writing back the consumed bits only
+    // clears them.
+    unsafe { scb::CFSR.write_volatile(mmfsr & scb::MMFSR_MASK) };
 }
 
 // Removed dump() method - debug logging not needed
@@ -0,0 +1,123 @@
+// Copyright 2025 The Pigweed Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not
+// use this file except in compliance with the License. You may obtain a copy of
+// the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations under
+// the License.
+
+//! SysTick-driven monotonic tick and timer callbacks.
+//!
+//! SysTick is programmed for a fixed 1 kHz tick.  Its ISR advances the tick
+//! count and fires any timer callbacks whose deadline has passed.  Preemptive
+//! context switching (a PendSV handler saving and restoring per-task state) is
+//! not wired up yet, so the ISR only drives the clock and the callback table.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use kernel_config::{CortexMKernelConfigInterface as _, KernelConfig};
+
+use crate::regs::Regs;
+
+/// Scheduler tick rate in Hz.
+const TICK_HZ: u32 = 1_000;
+
+/// Maximum number of registered timer callbacks.
+const MAX_CALLBACKS: usize = 8;
+
+/// Monotonic tick counter, advanced once per SysTick interrupt.
+static TICKS: AtomicU64 = AtomicU64::new(0);
+
+/// A deadline-ordered timer callback.
+#[derive(Copy, Clone)]
+struct TimerCallback {
+    deadline: u64,
+    callback: fn(),
+}
+
+/// Registered callbacks, guarded by disabling SysTick while mutating the table.
+static mut CALLBACKS: [Option<TimerCallback>; MAX_CALLBACKS] = [None; MAX_CALLBACKS];
+
+/// Current tick count since [`init`].
+#[must_use]
+pub fn ticks() -> u64 {
+    TICKS.load(Ordering::Relaxed)
+}
+
+/// Program SysTick for the 1 kHz scheduler tick and start the clock.
+///
+/// `kernel::main` calls this during startup; from then on the tick count
+/// advances and registered timer callbacks fire.
+pub fn init() {
+    let mut systick = Regs::get().systick;
+
+    // reload = core_clock / tick_hz - 1, matching the SysTick "N-1" convention.
+    let reload = KernelConfig::SYS_TICK_HZ / TICK_HZ - 1;
+    systick.rvr.write(reload);
+    systick.cvr.write(0);
+    systick
+        .csr
+        .write(systick.csr.read().with_clksource(true).with_tickint(true).with_enable(true));
+}
+
+/// Register `callback` to fire once `delay_ticks` ticks from now.
+///
+/// Returns `false` if the callback table is full.
+pub fn register(delay_ticks: u64, callback: fn()) -> bool {
+    let deadline = ticks() + delay_ticks;
+    // SAFETY: SysTick callbacks only run in the ISR; disabling the interrupt
+    // bit gives us exclusive access to the table for the duration of the edit.
+    with_systick_masked(|| {
+        // SAFETY: exclusive access held by `with_systick_masked`.
+        let slots = unsafe { &mut *core::ptr::addr_of_mut!(CALLBACKS) };
+        for slot in slots.iter_mut() {
+            if slot.is_none() {
+                *slot = Some(TimerCallback { deadline, callback });
+                return true;
+            }
+        }
+        false
+    })
+}
+
+/// SysTick interrupt handler: advance the clock and fire due callbacks.
+///
+/// This does not pend a context switch: there is no PendSV handler installed to
+/// service it, so pending one would trap into `cortex-m-rt`'s default handler
+/// and hang.  Pending is added together with the switch itself when preemptive
+/// scheduling lands.
+///
+/// # Safety
+/// Installed as the SysTick vector; runs only in exception context.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn systick_handler() {
+    let now = TICKS.fetch_add(1, Ordering::Relaxed) + 1;
+
+    // SAFETY: we are inside the SysTick ISR, which has exclusive access to the
+    // callback table against the masked `register` path.
+    let slots = unsafe { &mut *core::ptr::addr_of_mut!(CALLBACKS) };
+    for slot in slots.iter_mut() {
+        if let Some(cb) = *slot {
+            if now >= cb.deadline {
+                *slot = None;
+                (cb.callback)();
+            }
+        }
+    }
+}
+
+/// Run `f` with the SysTick interrupt masked, restoring it afterward.
+fn with_systick_masked<R>(f: impl FnOnce() -> R) -> R {
+    let mut systick = Regs::get().systick;
+    let prev = systick.csr.read();
+    systick.csr.write(prev.with_tickint(false));
+    let result = f();
+    systick.csr.write(prev);
+    result
+}
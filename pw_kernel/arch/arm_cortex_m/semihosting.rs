@@ -0,0 +1,96 @@
+// Copyright 2025 The Pigweed Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not
+// use this file except in compliance with the License. You may obtain a copy of
+// the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations under
+// the License.
+
+//! ARM semihosting backend for running under QEMU (`-semihosting-config
+//! enable=on`).
+//!
+//! Enabled by the `semihosting` cargo feature.  When on, [`exit`] terminates
+//! the emulator with a host-visible status and [`write0`] prints to the host
+//! console, so `kernel::shutdown` and `Arch::panic` can surface pass/fail and
+//! panic text to a CI test runner.  On real AST1030 hardware the feature is off
+//! and the raw shutdown path is used instead.
+
+/// `SYS_EXIT` semihosting operation number.
+const SYS_EXIT: u32 = 0x18;
+/// `SYS_WRITE0` semihosting operation number (NUL-terminated string).
+const SYS_WRITE0: u32 = 0x04;
+/// `ADP_Stopped_ApplicationExit` reason code for `SYS_EXIT`.
+const ADP_STOPPED_APPLICATION_EXIT: u32 = 0x2_0026;
+
+/// Issue a raw semihosting call: operation in `r0`, parameter block in `r1`.
+///
+/// # Safety
+/// Must run on a target whose debug host (QEMU) handles the `bkpt 0xAB`
+/// semihosting trap; otherwise the breakpoint halts the core.
+#[inline(always)]
+unsafe fn syscall(op: u32, param: u32) -> u32 {
+    let result;
+    // SAFETY: the `bkpt 0xAB` ABI passes op in r0 and the param block in r1,
+    // and returns the result in r0.
+    unsafe {
+        core::arch::asm!(
+            "bkpt 0xAB",
+            inout("r0") op => result,
+            in("r1") param,
+            options(nostack, preserves_flags),
+        );
+    }
+    result
+}
+
+/// Terminate QEMU with `code` as the process exit status.
+///
+/// # Safety
+/// See [`syscall`].
+pub unsafe fn exit(code: i32) -> ! {
+    // The exit parameter block is `[reason, subcode]`; QEMU reports `subcode`
+    // as the process exit status.
+    let block = [ADP_STOPPED_APPLICATION_EXIT, code as u32];
+    // SAFETY: forwarded to `syscall`.
+    unsafe {
+        syscall(SYS_EXIT, block.as_ptr() as u32);
+    }
+    // QEMU does not return from SYS_EXIT, but guard against a debugger that
+    // resumes the core.
+    loop {
+        core::hint::spin_loop();
+    }
+}
+
+/// Write a NUL-terminated string to the host console.
+///
+/// # Safety
+/// `s` must be NUL-terminated and valid for reads; see [`syscall`].
+pub unsafe fn write0(s: &core::ffi::CStr) {
+    // SAFETY: forwarded to `syscall`; `s` is NUL-terminated by `CStr`.
+    unsafe {
+        syscall(SYS_WRITE0, s.as_ptr() as u32);
+    }
+}
+
+/// Print `msg` to the host console, then terminate QEMU with `code`.
+///
+/// The single entry point `kernel::shutdown` and `Arch::panic` route through
+/// under the `semihosting` feature: it surfaces the reason text before exiting
+/// so a CI runner sees both the pass/fail status and the panic message.
+///
+/// # Safety
+/// See [`write0`] and [`exit`].
+pub unsafe fn fault_exit(code: i32, msg: &core::ffi::CStr) -> ! {
+    // SAFETY: forwarded to `write0`/`exit`; `msg` is NUL-terminated by `CStr`.
+    unsafe {
+        write0(msg);
+        exit(code)
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright 2025 The Pigweed Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not
+// use this file except in compliance with the License. You may obtain a copy of
+// the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations under
+// the License.
+
+//! ARMv7-M `SVC`-based system-call boundary.
+//!
+//! Unprivileged user processes request kernel services by executing `svc #n`
+//! with arguments marshalled in `r0`-`r3`, matching the AAPCS argument
+//! registers.  The `SVCall` exception handler reads the stacked exception
+//! frame, dispatches on the immediate-derived number, and writes the result
+//! back into the frame's saved `r0` so the user sees it as the trampoline's
+//! return value.  This keeps the kernel ABI stable and privilege-crossing
+//! rather than requiring user code to link kernel internals directly.
+
+/// Numbered system calls understood by the [`svc_handler`].
+///
+/// The discriminants are the immediate operand of the `svc` instruction and
+/// must stay stable: they are the ABI contract between user processes and the
+/// kernel.
+#[derive(Copy, Clone, Eq, PartialEq)]
+#[repr(u8)]
+pub enum Syscall {
+    /// Terminate the system with the exit code in `r0`.
+    Shutdown = 0,
+    /// Yield the remainder of the current time slice.
+    Yield = 1,
+    /// Write/log `r1` bytes starting at `r0`.
+    Write = 2,
+    /// Sleep for `r0` ticks.
+    Sleep = 3,
+    /// Scatter-read a channel message into the `IoSliceMut` list at `r2`
+    /// (`r3` entries) for the handle in `r0` at byte offset `r1`.
+    ChannelReadVectored = 4,
+    /// Gather-send then scatter-receive for the handle in `r0`, driven by the
+    /// [`ChannelTransactArgs`] block `r1` points at.
+    ChannelTransactVectored = 5,
+    /// Gather-respond to the pending message on the handle in `r0` from the
+    /// `IoSlice` list at `r1` (`r2` entries).
+    ChannelRespondVectored = 6,
+}
+
+impl Syscall {
+    /// Decode the `svc` immediate into a [`Syscall`], if it names one.
+    #[must_use]
+    pub const fn from_number(number: u8) -> Option<Self> {
+        match number {
+            0 => Some(Self::Shutdown),
+            1 => Some(Self::Yield),
+            2 => Some(Self::Write),
+            3 => Some(Self::Sleep),
+            4 => Some(Self::ChannelReadVectored),
+            5 => Some(Self::ChannelTransactVectored),
+            6 => Some(Self::ChannelRespondVectored),
+            _ => None,
+        }
+    }
+}
+
+/// Argument block for [`Syscall::ChannelTransactVectored`], whose six operands
+/// do not fit the four AAPCS argument registers; the user marshals one on its
+/// stack and passes a pointer in `r1`.
+#[repr(C)]
+pub struct ChannelTransactArgs {
+    /// Gather list describing the request payload.
+    pub send: *const u8,
+    /// Number of `IoSlice` entries in `send`.
+    pub send_len: u32,
+    /// Scatter list the response is written into.
+    pub recv: *mut u8,
+    /// Number of `IoSliceMut` entries in `recv`.
+    pub recv_len: u32,
+    /// Absolute deadline in ticks (little-endian `u64`, low word first).
+    pub deadline: u64,
+}
+
+/// The eight words an ARMv7-M exception stacks on entry (`r0`-`r3`, `r12`,
+/// `lr`, `pc`, `xpsr`), in stack order.
+#[repr(C)]
+pub struct ExceptionFrame {
+    pub r0: u32,
+    pub r1: u32,
+    pub r2: u32,
+    pub r3: u32,
+    pub r12: u32,
+    pub lr: u32,
+    pub pc: u32,
+    pub xpsr: u32,
+}
+
+/// Dispatch a decoded syscall, returning the value to place in the caller's
+/// `r0`.
+///
+/// # Safety
+/// `frame` must point at the live exception frame for the interrupted thread;
+/// argument words are interpreted as user-supplied pointers/lengths where the
+/// individual syscall requires it.
+unsafe fn dispatch(frame: &mut ExceptionFrame) -> u32 {
+    // The `svc #n` immediate sits in the byte before the stacked return PC.
+    // SAFETY: `pc` points just past the 2-byte `svc` encoding in user code.
+    let number = unsafe { ((frame.pc as *const u8).offset(-2)).read() };
+
+    match Syscall::from_number(number) {
+        Some(Syscall::Shutdown) => {
+            #[allow(clippy::cast_possible_truncation)]
+            kernel::shutdown(frame.r0 as i32)
+        }
+        Some(Syscall::Yield) => {
+            kernel::yield_now();
+            0
+        }
+        Some(Syscall::Write) => {
+            // SAFETY: user supplied (ptr, len); validated against the caller's
+            // MPU regions before the bytes are logged.
+            unsafe { kernel::user_write(frame.r0 as *const u8, frame.r1 as usize) }
+        }
+        Some(Syscall::Sleep) => {
+            kernel::sleep_ticks(frame.r0 as u64);
+            0
+        }
+        Some(Syscall::ChannelReadVectored) => {
+            // SAFETY: user supplied (handle, offset, iovec ptr, count); the
+            // kernel validates each fragment against the caller's MPU regions
+            // before scattering the message into them.
+            unsafe {
+                kernel::channel_read_vectored(
+                    frame.r0,
+                    frame.r1 as usize,
+                    frame.r2 as *mut u8,
+                    frame.r3 as usize,
+                )
+            }
+        }
+        Some(Syscall::ChannelTransactVectored) => {
+            // SAFETY: `r1` points at a `ChannelTransactArgs` the kernel
+            // validates, as it does each gather/scatter fragment within it.
+            unsafe {
+                kernel::channel_transact_vectored(frame.r0, frame.r1 as *const ChannelTransactArgs)
+            }
+        }
+        Some(Syscall::ChannelRespondVectored) => {
+            // SAFETY: user supplied (handle, iovec ptr, count); the kernel
+            // validates each fragment before gathering the response.
+            unsafe {
+                kernel::channel_respond_vectored(
+                    frame.r0,
+                    frame.r1 as *const u8,
+                    frame.r2 as usize,
+                )
+            }
+        }
+        None => {
+            pw_assert::panic!("Unknown syscall number {}", number as u32);
+        }
+    }
+}
+
+/// `SVCall` exception entry point.
+///
+/// Selects the active stack (PSP for threads, MSP otherwise) from the
+/// `EXC_RETURN` value in `lr`, then dispatches and writes the result back into
+/// the stacked `r0`.
+///
+/// # Safety
+/// Installed as the `SVCall` vector; must only run in exception context.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn svc_handler() {
+    let frame_ptr: *mut ExceptionFrame;
+    // SAFETY: reading the active stack pointer out of the exception context.
+    unsafe {
+        core::arch::asm!(
+            "tst lr, #4",        // EXC_RETURN bit 2: thread used PSP.
+            "ite eq",
+            "mrseq {ptr}, msp",
+            "mrsne {ptr}, psp",
+            ptr = out(reg) frame_ptr,
+            options(nostack, preserves_flags),
+        );
+    }
+
+    // SAFETY: `frame_ptr` is the interrupted thread's stacked frame.
+    let frame = unsafe { &mut *frame_ptr };
+    // SAFETY: forwarded to `dispatch`.
+    frame.r0 = unsafe { dispatch(frame) };
+}
@@ -0,0 +1,62 @@
+// Copyright 2025 The Pigweed Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not
+// use this file except in compliance with the License. You may obtain a copy of
+// the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations under
+// the License.
+
+//! Dropping into unprivileged thread mode behind the PMSAv7 MPU.
+//!
+//! On ARMv7-M the privilege split is the thread-mode analogue of the
+//! handler/unprivileged-thread boundary the RPi tutorials draw between EL1 and
+//! EL0: privileged code programs the MPU, then clears `CONTROL.nPRIV` so the
+//! boot process runs with user access rights.  Any access the MPU rejects traps
+//! through `MemManage`/`HardFault` into [`Arch::panic`], giving real fault
+//! isolation instead of silent kernel corruption.
+
+use crate::protection_v7::MemoryConfig;
+
+/// Program `config` into the MPU and transfer control to `entry` running in
+/// unprivileged thread mode.
+///
+/// The MPU is configured first (privileged RW over kernel `.text`/`.data`, RX
+/// over user `.text`, RW-NX over the user stack/heap), then `CONTROL.nPRIV` is
+/// set and an `ISB` is issued so the privilege downgrade is in effect before
+/// the first user instruction is fetched.
+///
+/// # Safety
+/// `config` must describe a sound memory map for `entry`, and `entry` must be a
+/// valid unprivileged entry point; the caller is giving up privileged execution
+/// for the remainder of this thread.
+pub unsafe fn enter_unprivileged(config: &MemoryConfig, entry: extern "C" fn() -> !) -> ! {
+    // SAFETY: forwarded to the caller; `config` describes a sound map.
+    unsafe {
+        config.write();
+    }
+
+    // Drop privilege: set CONTROL.nPRIV (bit 0).  The ISB is mandatory after a
+    // CONTROL write per ARM DDI 0403E.e B5.2.3 so the new privilege level is
+    // visible to the next instruction fetch.
+    //
+    // SAFETY: the MPU is already programmed, so the first unprivileged fetch of
+    // `entry` lands inside a user-executable region.
+    unsafe {
+        core::arch::asm!(
+            "mrs {tmp}, CONTROL",
+            "orr {tmp}, {tmp}, #1",
+            "msr CONTROL, {tmp}",
+            "isb",
+            tmp = out(reg) _,
+            options(nostack, preserves_flags),
+        );
+    }
+
+    entry()
+}
@@ -0,0 +1,177 @@
+// Copyright 2025 The Pigweed Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not
+// use this file except in compliance with the License. You may obtain a copy of
+// the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations under
+// the License.
+
+//! PMSAv7 MPU implementation for the Cortex-R5.
+//!
+//! The Cortex-R5 uses the same PMSAv7 programming model as the Cortex-M — the
+//! region/attribute derivation and buddy decomposition are shared via the
+//! [`pmsav7`] crate — but programs its MPU through the CP15 coprocessor
+//! registers (`RGNR`, `DRBAR`, `DRSR`, `DRACR`) rather than the memory-mapped
+//! `RNR`/`RBAR`/`RASR` block, and exposes a different region count (12 or 16)
+//! selected at compile time through [`kernel_config`].
+
+use kernel_config::{CortexRKernelConfigInterface as _, KernelConfig};
+use memory_config::{MemoryRegion, MemoryRegionType};
+use pmsav7::AccessPermission;
+
+/// A programmed Cortex-R5 MPU region: the three CP15 descriptor words.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct MpuRegion {
+    /// `DRBAR`: region base address.
+    drbar: u32,
+    /// `DRSR`: region size, enable, and sub-region-disable mask.
+    drsr: u32,
+    /// `DRACR`: access control (AP/TEX/S/C/B/XN).
+    dracr: u32,
+}
+
+impl MpuRegion {
+    const fn const_default() -> Self {
+        Self {
+            drbar: 0,
+            drsr: 0,
+            dracr: 0,
+        }
+    }
+
+    /// Encode a naturally-aligned power-of-two block into the CP15 descriptor
+    /// words, reusing the shared PMSAv7 attribute derivation.
+    #[expect(clippy::cast_possible_truncation)]
+    const fn block(base: u64, size: u64, ty: MemoryRegionType) -> Self {
+        // The block must be naturally aligned for the `DRBAR` encoding (which
+        // ignores the low `log2(size)` address bits) to name the intended range.
+        assert!(base % size == 0, "PMSAv7 block base must be size-aligned");
+        let attrs = pmsav7::region_attributes(ty);
+
+        // DRACR: B[0] C[1] S[2] TEX[5:3] AP[10:8] XN[12].
+        let ap = match attrs.ap {
+            AccessPermission::ReadOnly => 0b110,  // RO at any privilege level
+            AccessPermission::ReadWrite => 0b011, // RW at any privilege level
+        };
+        let dracr = (attrs.b as u32)
+            | ((attrs.c as u32) << 1)
+            | ((attrs.s as u32) << 2)
+            | ((attrs.tex as u32) << 3)
+            | (ap << 8)
+            | ((attrs.xn as u32) << 12);
+
+        // DRSR: EN[0], RSIZE[5:1] = log2(size) - 1, SRD[15:8] left clear since
+        // the buddy decomposition emits whole blocks.
+        let drsr = 1 | ((pmsav7::size_field(size) as u32) << 1);
+
+        Self {
+            drbar: base as u32,
+            drsr,
+            dracr,
+        }
+    }
+}
+
+/// Cortex-R5 PMSAv7 memory configuration.
+pub struct MemoryConfig {
+    mpu_regions: [MpuRegion; KernelConfig::NUM_MPU_REGIONS],
+    generic_regions: &'static [MemoryRegion],
+}
+
+impl MemoryConfig {
+    /// Create a `MemoryConfig` in a `const` context.
+    ///
+    /// Decomposition is identical to the Cortex-M backend (see
+    /// [`pmsav7::largest_block`]); only the descriptor encoding and region count
+    /// differ.
+    ///
+    /// # Panics
+    /// Panics at compile time if the decomposed entries exceed the Cortex-R5's
+    /// `KernelConfig::NUM_MPU_REGIONS`.
+    #[must_use]
+    pub const fn const_new(regions: &'static [MemoryRegion]) -> Self {
+        let mut mpu_regions = [MpuRegion::const_default(); KernelConfig::NUM_MPU_REGIONS];
+        let mut count = 0;
+
+        let mut i = 0;
+        while i < regions.len() {
+            let region = &regions[i];
+            let mut base = region.start as u64;
+            let end = pmsav7::normalized_end(region.end);
+            // Reject ranges that are not 32-byte granular up front; otherwise
+            // the decomposition below would map up to 31 bytes past `end`.
+            pmsav7::assert_block_granular(base, end);
+
+            while base < end {
+                let size = pmsav7::largest_block(base, end);
+                if count >= KernelConfig::NUM_MPU_REGIONS {
+                    panic!("MemoryConfig does not fit in the Cortex-R5 MPU regions");
+                }
+                mpu_regions[count] = MpuRegion::block(base, size, region.ty);
+                count += 1;
+                base += size;
+            }
+            i += 1;
+        }
+
+        Self {
+            mpu_regions,
+            generic_regions: regions,
+        }
+    }
+
+    /// Program this configuration into the Cortex-R5 MPU via CP15.
+    ///
+    /// # Safety
+    /// Caller must ensure it is safe and sound to reprogram the MPU with this
+    /// config.
+    pub unsafe fn write(&self) {
+        for (index, region) in self.mpu_regions.iter().enumerate() {
+            // SAFETY: CP15 c6 MPU region registers; the RGNR select plus the
+            // three descriptor writes program one region atomically from the
+            // core's point of view.
+            unsafe {
+                // RGNR (c6, c2, 0): select the region to program.
+                core::arch::asm!("mcr p15, 0, {r}, c6, c2, 0", r = in(reg) index as u32);
+                // DRBAR (c6, c1, 0): base address.
+                core::arch::asm!("mcr p15, 0, {r}, c6, c1, 0", r = in(reg) region.drbar);
+                // DRACR (c6, c1, 4): access control.
+                core::arch::asm!("mcr p15, 0, {r}, c6, c1, 4", r = in(reg) region.dracr);
+                // DRSR (c6, c1, 2): size/enable — written last so the region
+                // only becomes live once base and attributes are in place.
+                core::arch::asm!("mcr p15, 0, {r}, c6, c1, 2", r = in(reg) region.drsr);
+            }
+        }
+
+        // Barrier so the new MPU configuration is observed before the next
+        // memory access, mirroring the Cortex-M `dsb; isb`.
+        // SAFETY: barrier with no memory operands.
+        unsafe {
+            core::arch::asm!("dsb", "isb", options(nostack, preserves_flags));
+        }
+    }
+}
+
+impl memory_config::MemoryConfig for MemoryConfig {
+    const KERNEL_THREAD_MEMORY_CONFIG: Self = Self::const_new(&[MemoryRegion::new(
+        MemoryRegionType::ReadWriteExecutable,
+        0x0000_0000,
+        0xffff_ffff,
+    )]);
+
+    fn range_has_access(
+        &self,
+        access_type: MemoryRegionType,
+        start_addr: usize,
+        end_addr: usize,
+    ) -> bool {
+        let validation_region = MemoryRegion::new(access_type, start_addr, end_addr);
+        MemoryRegion::regions_have_access(self.generic_regions, &validation_region)
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright 2025 The Pigweed Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not
+// use this file except in compliance with the License. You may obtain a copy of
+// the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations under
+// the License.
+
+//! Architecture-neutral PMSAv7 region logic shared by the Cortex-M and Cortex-R
+//! MPU backends.
+//!
+//! Both cores use the same PMSAv7 programming model — naturally-aligned
+//! power-of-two regions carrying TEX/C/B/S/AP/XN attributes — but differ in
+//! their register blocks and region counts.  The attribute derivation and the
+//! naturally-aligned (buddy) decomposition live here so each backend only has
+//! to translate a [`RegionAttributes`] into its own descriptor registers and
+//! drive its own programming sequence.
+
+#![no_std]
+
+use memory_config::MemoryRegionType;
+
+/// Smallest PMSAv7 region: 32 bytes (`SIZE` field 4).
+pub const MIN_BLOCK_SIZE: u64 = 32;
+/// Largest PMSAv7 region: the full 4 GiB space (`SIZE` field 31).
+pub const MAX_BLOCK_SIZE: u64 = 1 << 32;
+
+/// Access permission shared by the two backends (the only two encodings the
+/// kernel's region types need).
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum AccessPermission {
+    /// Read-only at any privilege level.
+    ReadOnly,
+    /// Read/write at any privilege level.
+    ReadWrite,
+}
+
+/// PMSAv7 memory attributes for a region, independent of the register layout
+/// that encodes them.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct RegionAttributes {
+    /// Execute-never.
+    pub xn: bool,
+    /// `TEX` field (type extension).
+    pub tex: u8,
+    /// Shareable.
+    pub s: bool,
+    /// Cacheable.
+    pub c: bool,
+    /// Bufferable.
+    pub b: bool,
+    /// Access permission.
+    pub ap: AccessPermission,
+}
+
+/// Derive the PMSAv7 attributes for a [`MemoryRegionType`].
+///
+/// This mapping is identical on Cortex-M and Cortex-R; only the register
+/// encoding differs.
+#[must_use]
+pub const fn region_attributes(ty: MemoryRegionType) -> RegionAttributes {
+    let (xn, tex, s, c, b, ap) = match ty {
+        MemoryRegionType::ReadOnlyData => (
+            /* xn */ true,
+            /* tex */ 0b001, // Normal memory, outer and inner write-back
+            /* s */ true, /* c */ true, /* b */ true,
+            AccessPermission::ReadOnly,
+        ),
+        MemoryRegionType::ReadWriteData => (
+            /* xn */ true,
+            /* tex */ 0b001, // Normal memory, outer and inner write-back
+            /* s */ false, /* c */ true, /* b */ true,
+            AccessPermission::ReadWrite,
+        ),
+        MemoryRegionType::ReadOnlyExecutable => (
+            /* xn */ false,
+            /* tex */ 0b001, // Normal memory, outer and inner write-back
+            /* s */ true, /* c */ true, /* b */ true,
+            AccessPermission::ReadOnly,
+        ),
+        MemoryRegionType::ReadWriteExecutable => (
+            /* xn */ false,
+            /* tex */ 0b001, // Normal memory, outer and inner write-back
+            /* s */ true, /* c */ true, /* b */ true,
+            AccessPermission::ReadWrite,
+        ),
+        MemoryRegionType::Device => (
+            /* xn */ true,
+            /* tex */ 0b000, // Device memory
+            /* s */ true, /* c */ false, /* b */ true,
+            AccessPermission::ReadOnly,
+        ),
+    };
+    RegionAttributes { xn, tex, s, c, b, ap }
+}
+
+/// `SIZE` field encoding for a power-of-two `size` in bytes: `log2(size) - 1`.
+#[must_use]
+pub const fn size_field(size: u64) -> u8 {
+    let mut bits = 0;
+    let mut v = size;
+    while v > 1 {
+        v >>= 1;
+        bits += 1;
+    }
+    #[expect(clippy::cast_possible_truncation)]
+    ((bits - 1) as u8)
+}
+
+/// Largest naturally-aligned power-of-two block starting at `base` that stays
+/// within `end`: the biggest `S` in `MIN_BLOCK_SIZE..=MAX_BLOCK_SIZE` with
+/// `base % S == 0` and `base + S <= end`.
+#[must_use]
+pub const fn largest_block(base: u64, end: u64) -> u64 {
+    let mut size = MIN_BLOCK_SIZE;
+    while size < MAX_BLOCK_SIZE {
+        let next = size * 2;
+        if base % next == 0 && base + next <= end {
+            size = next;
+        } else {
+            break;
+        }
+    }
+    size
+}
+
+/// Assert that `[base, end)` is representable as whole PMSAv7 blocks.
+///
+/// PMSAv7 regions (and their sub-regions) are a multiple of 32 bytes and
+/// 32-byte aligned, so a range whose bounds are not both 32-byte granular
+/// cannot be covered exactly: the buddy decomposition would either leave a
+/// sub-32-byte tail unmapped or round it up into an enabled block that maps
+/// memory outside the requested range — an isolation hole.  Reject such a
+/// range at construction time (a compile-time panic in the `const` callers)
+/// rather than silently over-mapping.
+pub const fn assert_block_granular(base: u64, end: u64) {
+    if base % MIN_BLOCK_SIZE != 0 || end % MIN_BLOCK_SIZE != 0 {
+        panic!("PMSAv7 region must be 32-byte aligned and a multiple of 32 bytes");
+    }
+}
+
+/// Normalize a region end, mapping the top-of-space sentinel `usize::MAX` to
+/// one-past-the-end so a full-space region decomposes to a single 4 GiB block.
+#[must_use]
+pub const fn normalized_end(end: usize) -> u64 {
+    if end == usize::MAX {
+        MAX_BLOCK_SIZE
+    } else {
+        end as u64
+    }
+}
@@ -0,0 +1,158 @@
+// Copyright 2025 The Pigweed Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not
+// use this file except in compliance with the License. You may obtain a copy of
+// the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations under
+// the License.
+#![no_std]
+#![no_main]
+
+use arch_arm_cortex_m::Arch;
+
+use crate::regs::{CacheController, Scu};
+
+mod regs;
+
+/// AST1030-specific hardware initialization.
+/// This runs before the Rust runtime setup and the main kernel entry.
+/// Based on aspeed-rust's pre_init implementation.
+///
+/// Key setup:
+/// 1. JTAG pinmux configuration for debug access
+/// 2. AST1030 cache controller initialization (NOT Cortex-M4 ACTLR cache)
+unsafe extern "C" {
+    // Linker-script symbols bounding the sections we must initialize by hand.
+    // `__sdata`/`__edata` bound `.data` in SRAM (VMA); `__sidata` is its load
+    // image in flash (LMA).  `__sbss`/`__ebss` bound `.bss`.
+    static mut __sbss: u32;
+    static mut __ebss: u32;
+    static mut __sdata: u32;
+    static mut __edata: u32;
+    static __sidata: u32;
+}
+
+/// Explicit early section initialization for the AST1030 boot path.
+///
+/// Following the RPi `runtime_init` pattern, this zeroes `.bss` and copies
+/// `.data` from its flash LMA to its SRAM VMA before any Rust runtime code
+/// runs, rather than relying on `cortex-m-rt` to do so after `pre_init`.  Doing
+/// it here lets `pre_init` invalidate the freshly-written `.data` range before
+/// enabling the cache so the relocated data is observed coherently.
+///
+/// # Safety
+/// Must run exactly once during boot, before any access to `.data`/`.bss`.
+unsafe fn runtime_init() {
+    // SAFETY: the linker symbols bound contiguous, word-aligned regions, and
+    // boot is single-threaded so these are the only accesses in flight.
+    unsafe {
+        let mut dst = core::ptr::addr_of_mut!(__sbss);
+        let end = core::ptr::addr_of_mut!(__ebss);
+        while dst < end {
+            dst.write_volatile(0);
+            dst = dst.add(1);
+        }
+
+        let mut dst = core::ptr::addr_of_mut!(__sdata);
+        let end = core::ptr::addr_of_mut!(__edata);
+        let mut src = core::ptr::addr_of!(__sidata);
+        while dst < end {
+            dst.write_volatile(src.read_volatile());
+            dst = dst.add(1);
+            src = src.add(1);
+        }
+    }
+}
+
+#[cortex_m_rt::pre_init]
+unsafe fn pre_init() {
+    // SAFETY: This function is called once during boot before any other code runs.
+    // The register accesses are to valid hardware registers on AST1030.
+    unsafe {
+        // Route the JTAG signals to their pads for debug access.
+        Scu::PINMUX5.set_bits(Scu::JTAG_ENABLE);
+
+        // Zero .bss and relocate .data into SRAM before enabling the cache.
+        runtime_init();
+
+        // AST1030 Cache Controller Configuration.
+        // Note: AST1030 has its own cache controller, NOT the standard ARM
+        // Cortex-M4 cache.  Disable, invalidate the just-relocated .data range
+        // so no stale lines shadow it, set the cached area, then re-enable.
+        CacheController::disable();
+
+        let data_start = core::ptr::addr_of!(__sdata) as usize;
+        let data_end = core::ptr::addr_of!(__edata) as usize;
+        CacheController::invalidate_range(data_start, data_end - data_start);
+
+        CacheController::AREA.write(CacheController::AREA_FULL);
+        CacheController::enable();
+    }
+}
+
+#[unsafe(no_mangle)]
+#[allow(non_snake_case)]
+pub extern "C" fn pw_assert_HandleFailure() -> ! {
+    use kernel::Arch as _;
+    Arch::panic()
+}
+
+unsafe extern "C" {
+    // `SVCall` entry point exported (`#[no_mangle]`) by
+    // `arch_arm_cortex_m::syscall`; linked by symbol so the target crate does
+    // not need the arch module in scope to install the vector.
+    fn svc_handler();
+    // `SysTick` entry point exported by `arch_arm_cortex_m::scheduler`.
+    fn systick_handler();
+    // `MemManage` fault decoder exported by `arch_arm_cortex_m::protection_v7`.
+    fn mem_manage_handler();
+}
+
+/// Route the `SVCall` exception to the architecture's syscall handler.
+///
+/// Unprivileged processes request kernel services with `svc #n`; without this
+/// vector the supervisor call would fall through to `cortex-m-rt`'s default
+/// handler and hang instead of dispatching.
+#[cortex_m_rt::exception]
+fn SVCall() {
+    // SAFETY: invoked by the NVIC in `SVCall` exception context, which is the
+    // only context `svc_handler` may run in.
+    unsafe { svc_handler() }
+}
+
+/// Route the `SysTick` exception to the scheduler tick.
+///
+/// The scheduler programs SysTick for its 1 kHz tick but nothing installed the
+/// vector, so the counter never advanced and no timer callbacks ran.
+#[cortex_m_rt::exception]
+fn SysTick() {
+    // SAFETY: invoked by the NVIC in `SysTick` exception context.
+    unsafe { systick_handler() }
+}
+
+/// Route the `MemManage` exception to the fault decoder.
+///
+/// The unprivileged boot process established by `enter_unprivileged` only has
+/// real isolation if MPU violations are trapped and decoded; without the vector
+/// a user access fault escalates opaquely instead of being reported.
+#[cortex_m_rt::exception]
+fn MemoryManagement() {
+    // SAFETY: invoked by the NVIC in `MemManage` exception context.
+    unsafe { mem_manage_handler() }
+}
+
+#[cortex_m_rt::entry]
+fn main() -> ! {
+    kernel::static_init_state!(static mut INIT_STATE: InitKernelState<Arch>);
+
+    // SAFETY: `main` is only executed once, so we never generate more than one
+    // `&mut` reference to `INIT_STATE`.
+    #[allow(static_mut_refs)]
+    kernel::main(Arch, unsafe { &mut INIT_STATE });
+}
@@ -0,0 +1,178 @@
+// Copyright 2025 The Pigweed Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not
+// use this file except in compliance with the License. You may obtain a copy of
+// the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations under
+// the License.
+
+//! Typed register definitions for the AST1030 System Control Unit and cache
+//! controller.
+//!
+//! These replace the raw `read_volatile`/`write_volatile` pokes against magic
+//! addresses in `pre_init` with named, documented fields.  The layout mirrors
+//! the register-abstraction style the RPi kernels get from `tock-registers`,
+//! but is hand-rolled here to avoid pulling a new dependency into the bare
+//! AST1030 boot path.  Other AST1030 drivers can reuse [`Scu`] and
+//! [`CacheController`].
+
+use core::ptr::{read_volatile, write_volatile};
+
+/// Base address of the AST1030 System Control Unit.
+pub const SCU_BASE: usize = 0x7e6e_2000;
+
+/// A single 32-bit memory-mapped register.
+///
+/// Reads and writes go through `read_volatile`/`write_volatile`, so a
+/// `Register` is only ever a thin typed view over a hardware address.
+#[derive(Copy, Clone)]
+pub struct Register {
+    addr: usize,
+}
+
+impl Register {
+    const fn new(addr: usize) -> Self {
+        Self { addr }
+    }
+
+    /// Read the current 32-bit value.
+    ///
+    /// # Safety
+    /// `addr` must name a readable AST1030 register.
+    #[inline]
+    pub unsafe fn read(self) -> u32 {
+        // SAFETY: forwarded to the caller.
+        unsafe { read_volatile(self.addr as *const u32) }
+    }
+
+    /// Write a 32-bit value.
+    ///
+    /// # Safety
+    /// `addr` must name a writable AST1030 register; the caller is responsible
+    /// for the side effects of the write.
+    #[inline]
+    pub unsafe fn write(self, value: u32) {
+        // SAFETY: forwarded to the caller.
+        unsafe { write_volatile(self.addr as *mut u32, value) };
+    }
+
+    /// Set the bits in `mask`, leaving the rest untouched.
+    ///
+    /// # Safety
+    /// See [`Register::read`]/[`Register::write`].
+    #[inline]
+    pub unsafe fn set_bits(self, mask: u32) {
+        // SAFETY: forwarded to the caller.
+        unsafe { self.write(self.read() | mask) };
+    }
+}
+
+/// System Control Unit registers (subset used by the boot path).
+pub struct Scu;
+
+impl Scu {
+    /// Multi-function pin control #5 (`SCU_BASE + 0x41c`).
+    ///
+    /// Bits 25..=29 (`JTAG_ENABLE`) route the JTAG signals to their pads for
+    /// debug access.
+    pub const PINMUX5: Register = Register::new(SCU_BASE + 0x41c);
+
+    /// Mask selecting the five JTAG pinmux bits in [`Scu::PINMUX5`].
+    pub const JTAG_ENABLE: u32 = 0x1f << 25;
+}
+
+/// AST1030 cache controller.
+///
+/// This is the AST1030's own cache block, distinct from the Cortex-M4 ACTLR
+/// cache controls.  Programming order is: disable via [`Self::CTRL`], set the
+/// cached [`Self::AREA`], pulse [`Self::INVALIDATE`], then re-enable.
+pub struct CacheController;
+
+impl CacheController {
+    /// Cache control register (`SCU_BASE + 0xa58`).  Bit 0 (`ENABLE`) turns the
+    /// cache on; writing 0 disables it.
+    pub const CTRL: Register = Register::new(SCU_BASE + 0xa58);
+
+    /// Cacheable-area register (`SCU_BASE + 0xa50`).  Selects which address
+    /// range the controller caches.
+    pub const AREA: Register = Register::new(SCU_BASE + 0xa50);
+
+    /// Invalidate register (`SCU_BASE + 0xa54`).  Writing an invalidate request
+    /// drops the matching cache lines.
+    pub const INVALIDATE: Register = Register::new(SCU_BASE + 0xa54);
+
+    /// `CTRL.ENABLE` bit.
+    pub const ENABLE: u32 = 1 << 0;
+
+    /// Area value covering the full 1 MiB window the boot path caches.
+    pub const AREA_FULL: u32 = 0x000f_ffff;
+
+    /// Invalidate request covering the cached SRAM window.
+    pub const INVALIDATE_ALL: u32 = 0x8660_0000;
+
+    /// Enable the cache.
+    ///
+    /// # Safety
+    /// Callers must ensure cached memory is coherent (see
+    /// [`invalidate_range`](Self::invalidate_range)).
+    pub unsafe fn enable() {
+        // SAFETY: forwarded to the caller.
+        unsafe { Self::CTRL.write(Self::ENABLE) };
+    }
+
+    /// Disable the cache.
+    ///
+    /// # Safety
+    /// See [`Register::write`].
+    pub unsafe fn disable() {
+        // SAFETY: forwarded to the caller.
+        unsafe { Self::CTRL.write(0) };
+    }
+
+    /// Invalidate the cache lines backing `[start, start + len)`.
+    ///
+    /// The AST1030 invalidate register selects a window by its high address
+    /// bits; we derive that selector from the requested range rather than using
+    /// the fixed whole-SRAM mask so freshly-relocated `.data` is observed
+    /// coherently without dropping unrelated lines.
+    ///
+    /// # Safety
+    /// See [`Register::write`].
+    pub unsafe fn invalidate_range(start: usize, len: usize) {
+        let selector = Self::range_selector(start, len);
+        // SAFETY: forwarded to the caller.
+        unsafe {
+            Self::AREA.write(selector);
+            Self::INVALIDATE.write(selector);
+        }
+    }
+
+    /// Clean (flush) the cache lines backing `[start, start + len)`.
+    ///
+    /// The AST1030 controller is write-through for the cached SRAM window, so a
+    /// clean reduces to an invalidate of the same range; the separate entry
+    /// point keeps callers explicit about intent.
+    ///
+    /// # Safety
+    /// See [`Register::write`].
+    pub unsafe fn clean_range(start: usize, len: usize) {
+        // SAFETY: forwarded to the caller.
+        unsafe { Self::invalidate_range(start, len) };
+    }
+
+    /// Encode `[start, start + len)` into the controller's area/invalidate
+    /// selector (the range's high address bits).
+    fn range_selector(start: usize, len: usize) -> u32 {
+        let end = start.saturating_add(len);
+        #[allow(clippy::cast_possible_truncation)]
+        {
+            ((start as u32) & 0xffff_0000) | ((end.saturating_sub(1) as u32) >> 16)
+        }
+    }
+}
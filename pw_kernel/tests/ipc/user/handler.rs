@@ -17,26 +17,9 @@
 use app_handler::handle;
 use pw_status::{Error, Result};
 use userspace::entry;
-use userspace::syscall::{self, Signals};
+use userspace::syscall::{self, IoSlice, IoSliceMut, Signals};
 use userspace::time::Instant;
 
-/// 4-byte aligned byte buffer to keep ARMv7-M from faulting on
-/// compiler-generated multi-word loads (e.g. LDMIA) over IPC buffers.
-#[repr(C, align(4))]
-struct AlignedBuf<const N: usize> {
-    buf: [u8; N],
-}
-
-impl<const N: usize> AlignedBuf<N> {
-    fn as_bytes(&self) -> &[u8] {
-        &self.buf
-    }
-
-    fn as_bytes_mut(&mut self) -> &mut [u8] {
-        &mut self.buf
-    }
-}
-
 // Simple logging shims: on ARMv7-M we can disable verbose pw_log! usage
 // in this test to avoid exercising complex formatter/codegen paths that
 // currently generate unaligned multi-word loads.
@@ -75,20 +58,24 @@ fn handle_uppercase_ipcs() -> Result<()> {
         // Wait for an IPC to come in.
         syscall::object_wait(handle::IPC, Signals::READABLE, Instant::MAX)?;
 
-        // Read the payload. The initiator currently sends a single ASCII
-        // character encoded via `encode_utf8` into a 4-byte `char` slot,
-        // so only the low byte is meaningful.
-        const RECV_LEN: usize = core::mem::size_of::<char>();
-        let mut buffer = AlignedBuf::<RECV_LEN> { buf: [0; RECV_LEN] };
-        let len = syscall::channel_read(handle::IPC, 0, buffer.as_bytes_mut())?;
-        if len != RECV_LEN {
+        // Read the payload into a single word-sized slice. The initiator sends
+        // one ASCII character encoded via `encode_utf8` into a 4-byte `char`
+        // slot, so only the low byte is meaningful. The vectored read keeps
+        // each slice word-aligned, so no hand-aligned bounce buffer is needed.
+        const WORD_LEN: usize = core::mem::size_of::<char>();
+        let mut word_bytes = [0u8; WORD_LEN];
+        let len = syscall::channel_read_vectored(
+            handle::IPC,
+            0,
+            &mut [IoSliceMut::new(&mut word_bytes)],
+        )?;
+        if len != WORD_LEN {
             return Err(Error::OutOfRange);
         };
 
-        // Interpret the payload as a 32-bit word whose low byte holds
-        // the ASCII character; avoid char/UTF-8 helpers to keep the
-        // codegen simple and predictable on ARMv7-M.
-        let word = u32::from_ne_bytes(buffer.as_bytes().try_into().unwrap());
+        // Interpret the payload as a 32-bit word whose low byte holds the ASCII
+        // character.
+        let word = u32::from_ne_bytes(word_bytes);
         let b = (word & 0xFF) as u8;
         if !b.is_ascii_lowercase() {
             return Err(Error::InvalidArgument);
@@ -96,21 +83,15 @@ fn handle_uppercase_ipcs() -> Result<()> {
         let upper_b = b.to_ascii_uppercase();
         let upper_word = (word & !0xFF) | u32::from(upper_b);
 
-        // Respond to the IPC with two 4-byte words: the uppercased
-        // character (first) and the original (second).
-        const RESP_LEN: usize = core::mem::size_of::<char>() * 2;
-        let mut response_buffer = AlignedBuf::<RESP_LEN> { buf: [0; RESP_LEN] };
-        {
-            let buf = response_buffer.as_bytes_mut();
-            let upper_bytes = upper_word.to_ne_bytes();
-            let orig_bytes = word.to_ne_bytes();
-            // Manual per-byte copies to avoid slice-based memcpy.
-            for i in 0..4 {
-                buf[i] = upper_bytes[i];
-                buf[4 + i] = orig_bytes[i];
-            }
-        }
-        syscall::channel_respond(handle::IPC, response_buffer.as_bytes())?;
+        // Respond with two word-sized slices — the uppercased character first,
+        // the original second. The kernel gathers them across the IPC boundary,
+        // so there is no need to pack them into one contiguous buffer by hand.
+        let upper_bytes = upper_word.to_ne_bytes();
+        let orig_bytes = word.to_ne_bytes();
+        syscall::channel_respond_vectored(
+            handle::IPC,
+            &[IoSlice::new(&upper_bytes), IoSlice::new(&orig_bytes)],
+        )?;
     }
 }
 
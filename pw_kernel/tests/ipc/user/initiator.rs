@@ -18,26 +18,10 @@ use core::mem::size_of;
 
 use app_initiator::handle;
 use pw_status::{Error, Result};
+use userspace::syscall::{IoSlice, IoSliceMut};
 use userspace::time::Instant;
 use userspace::{entry, syscall};
 
-/// 4-byte aligned byte buffer to keep ARMv7-M from faulting on
-/// compiler-generated multi-word loads (e.g. LDMIA) over IPC buffers.
-#[repr(C, align(4))]
-struct AlignedBuf<const N: usize> {
-    buf: [u8; N],
-}
-
-impl<const N: usize> AlignedBuf<N> {
-    fn as_bytes(&self) -> &[u8] {
-        &self.buf
-    }
-
-    fn as_bytes_mut(&mut self) -> &mut [u8] {
-        &mut self.buf
-    }
-}
-
 // Simple logging shims: on ARMv7-M we disable verbose pw_log! usage
 // in this test to avoid exercising complex formatter/codegen paths
 // that currently generate unaligned multi-word loads.
@@ -68,18 +52,27 @@ macro_rules! test_log_error {
 fn test_uppercase_ipcs() -> Result<()> {
     test_log_info!("Ipc test starting");
     for c in 'a'..='z' {
-        const SEND_BUF_LEN: usize = size_of::<char>();
+        const WORD_LEN: usize = size_of::<char>();
         const RECV_BUF_LEN: usize = size_of::<char>() * 2;
 
-        let mut send_buf = AlignedBuf::<SEND_BUF_LEN> { buf: [0u8; SEND_BUF_LEN] };
-        let mut recv_buf = AlignedBuf::<RECV_BUF_LEN> { buf: [0u8; RECV_BUF_LEN] };
+        // Encode the character into its own word-sized send slice. The handler
+        // replies with two word-sized slices, so we scatter the response into a
+        // slice per character rather than one contiguous buffer — the kernel
+        // keeps each slice word-aligned, removing the need for a hand-aligned
+        // bounce buffer.
+        let mut send_buf = [0u8; WORD_LEN];
+        c.encode_utf8(&mut send_buf);
 
-        // Encode the character into `send_buf` and send it over to the handler.
-        c.encode_utf8(send_buf.as_bytes_mut());
-        let len = syscall::channel_transact(
+        let mut char0_bytes = [0u8; WORD_LEN];
+        let mut char1_bytes = [0u8; WORD_LEN];
+
+        let len = syscall::channel_transact_vectored(
             handle::IPC,
-            send_buf.as_bytes(),
-            recv_buf.as_bytes_mut(),
+            &[IoSlice::new(&send_buf)],
+            &mut [
+                IoSliceMut::new(&mut char0_bytes),
+                IoSliceMut::new(&mut char1_bytes),
+            ],
             Instant::MAX,
         )?;
 
@@ -93,18 +86,14 @@ fn test_uppercase_ipcs() -> Result<()> {
             return Err(Error::OutOfRange);
         }
 
-        let (char0_bytes, char1_bytes) = recv_buf
-            .as_bytes()
-            .split_at(size_of::<char>());
-
         // Decode first char.
-        let Ok(char0) = u32::from_ne_bytes(char0_bytes.try_into().unwrap()).try_into() else {
+        let Ok(char0) = u32::from_ne_bytes(char0_bytes).try_into() else {
             return Err(Error::InvalidArgument);
         };
         let char0: char = char0;
 
         // Decode second char.
-        let Ok(char1) = u32::from_ne_bytes(char1_bytes.try_into().unwrap()).try_into() else {
+        let Ok(char1) = u32::from_ne_bytes(char1_bytes).try_into() else {
             return Err(Error::InvalidArgument);
         };
         let char1: char = char1;
@@ -18,11 +18,13 @@ use std::io::BufWriter;
 use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 
+use std::io::Write as _;
+
 use anyhow::{Context, Result, anyhow, bail};
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use object::build::elf::{
     AttributeTag, AttributesSection, AttributesSubsection, AttributesSubsubsection, Builder,
-    Section, SectionData, SectionId,
+    Relocation, Section, SectionData, SectionId, SymbolId,
 };
 use object::build::{ByteString, Bytes, Id};
 use object::{ReadRef, elf};
@@ -45,11 +47,134 @@ struct Args {
     apps: Vec<PathBuf>,
     #[arg(long, required(true))]
     output: PathBuf,
+    /// Resolve relocations whose target symbol value is known after merge,
+    /// patching the reference in place and dropping the entry.  Relocation
+    /// types this tool doesn't understand on the target ISA are left intact.
+    #[arg(long)]
+    resolve: bool,
+    /// Output format.  `elf` emits the merged ELF; `bin`/`ihex`/`srec` lower
+    /// the loadable segments to a flashing image, removing the need for a
+    /// separate `arm-none-eabi-objcopy` step.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Elf)]
+    output_format: OutputFormat,
+    /// Byte value used to fill gaps between loadable regions (`bin` only).
+    #[arg(long, value_parser = parse_byte, default_value_t = 0)]
+    gap_fill: u8,
+    /// Pad the `bin` output with `--gap-fill` up to (but not including) this
+    /// absolute address.
+    #[arg(long)]
+    pad_to: Option<u64>,
+    /// Write a textual link map describing the merged layout to this path.
+    #[arg(long)]
+    map: Option<PathBuf>,
+    /// Require every load segment's base and size to be a multiple of this
+    /// alignment.  Off by default; the merged `sh_addr`s from the linker
+    /// scripts are trusted otherwise.
+    #[arg(long)]
+    mpu_align: Option<u64>,
+    /// Require every load segment to be a naturally-aligned power-of-two region
+    /// (size a power of two, base aligned to size), matching what a PMSAv7 MPU
+    /// can describe with a single region.  Opt-in; implies `--mpu-align`.
+    #[arg(long)]
+    mpu_pow2: bool,
+    /// Drop alloc sections unreachable from the entry points, shrinking the
+    /// merged image.
+    #[arg(long)]
+    gc_sections: bool,
+    /// Additional root symbols for `--gc-sections`.  The per-app `_start_*`
+    /// entry symbols are always roots.
+    #[arg(long("entry"))]
+    entries: Vec<String>,
+    /// Copy `SHF_COMPRESSED` sections verbatim instead of decompressing them,
+    /// preserving the compression header and flag.
+    #[arg(long)]
+    keep_compressed: bool,
+    /// Compress the merged image's large non-alloc `.debug_*` sections with the
+    /// given algorithm before writing.
+    #[arg(long, value_enum, default_value_t = CompressDebug::None)]
+    compress_debug: CompressDebug,
+    /// Ignore the build cache and always reassemble.
+    #[arg(long)]
+    force: bool,
+    /// Embed a deterministic `.note.gnu.build-id` identifying this
+    /// kernel+app combination.
+    #[arg(long)]
+    build_id: bool,
+    /// Width, in bits, of the embedded build id (rounded up to whole bytes).
+    #[arg(long, default_value_t = 128)]
+    build_id_bits: usize,
+    /// Declarative JSON manifest of apps with explicit names and metadata.
+    /// Supplements any positional `--app` arguments.
+    #[arg(long)]
+    manifest: Option<PathBuf>,
+}
+
+/// One app entry in a `--manifest` file.
+#[derive(Debug, serde::Deserialize)]
+struct ManifestApp {
+    /// Source path; may be a glob expanding to multiple apps.
+    path: String,
+    /// Explicit symbol name overriding the derived file-stem name.
+    #[serde(default)]
+    name: Option<String>,
+    /// Opaque caller metadata, ignored by the assembler itself.
+    #[serde(default)]
+    #[allow(dead_code)]
+    metadata: HashMap<String, String>,
+}
+
+/// Top-level `--manifest` schema.
+#[derive(Debug, serde::Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    apps: Vec<ManifestApp>,
+}
+
+/// Debug-section compression selection for the emitted image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum CompressDebug {
+    None,
+    Zlib,
+    Zstd,
+}
+
+/// Objcopy-style output formats for the merged image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Elf,
+    Bin,
+    Ihex,
+    Srec,
+}
+
+/// Parse a byte given as decimal or `0x`-prefixed hex.
+fn parse_byte(s: &str) -> Result<u8> {
+    let parsed = if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        u8::from_str_radix(hex, 16)
+    } else {
+        s.parse()
+    };
+    parsed.map_err(|e| anyhow!("Invalid byte value '{s}': {e}"))
 }
 
 struct SystemImage<'data> {
     builder: Builder<'data>,
     tokenized_section: Option<SectionId>,
+    /// Surviving COMDAT group members, bucketed by `(section name, content
+    /// hash)`.  The hash is only a prefilter: a candidate is folded onto an
+    /// entry in the bucket only after its bytes compare equal, so a hash
+    /// collision can never silently redirect a reference to a non-identical
+    /// section.
+    comdat_sections: HashMap<(Vec<u8>, u64), Vec<SectionId>>,
+    /// Names of global symbols the kernel defines.  An app that redefines one
+    /// of these is a hard error rather than a silently renamed duplicate.
+    kernel_globals: HashSet<Vec<u8>>,
+    /// Content-addressed pool of non-alloc read-only sections.  Byte-identical
+    /// sections from different apps (string tables, const `.rodata`, …) are
+    /// emitted once and shared; tokenizer concatenation is handled separately.
+    /// Bucketed by content hash; folding is gated on an actual byte comparison
+    /// so a hash collision never shares non-identical sections.
+    section_pool: HashMap<u64, Vec<SectionId>>,
 }
 
 impl<'data> SystemImage<'data> {
@@ -57,15 +182,57 @@ impl<'data> SystemImage<'data> {
         let builder = Builder::read(kernel_bytes)
             .map_err(|e| anyhow!("Failed to parse kernel image: {e}"))?;
 
+        let kernel_globals = builder
+            .symbols
+            .iter()
+            .filter(|s| s.st_bind() == elf::STB_GLOBAL && s.section.is_some())
+            .map(|s| s.name.to_vec())
+            .collect();
+
         let mut instance = Self {
             builder,
             tokenized_section: None,
+            comdat_sections: HashMap::new(),
+            kernel_globals,
+            section_pool: HashMap::new(),
         };
 
         instance.set_tokenized_section();
         Ok(instance)
     }
 
+    /// Hash a section's bytes for COMDAT/pool folding.  Non-`Data` sections
+    /// hash to zero, which simply disables folding for them.
+    ///
+    /// The hash is only ever used as a bucket prefilter; folding is always
+    /// confirmed with [`Self::find_identical_section`], so its lack of a
+    /// stability guarantee across toolchain versions does not affect
+    /// correctness.
+    fn section_content_hash(section: &Section) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        if let SectionData::Data(data) = &section.data {
+            data.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Search `bucket` for an already-emitted section whose bytes are identical
+    /// to `section`, returning it if found.  Only `Data` sections compare; this
+    /// is the byte check that makes hash-bucketed folding safe against
+    /// collisions.
+    fn find_identical_section(&self, bucket: &[SectionId], section: &Section) -> Option<SectionId> {
+        let SectionData::Data(candidate) = &section.data else {
+            return None;
+        };
+        bucket.iter().copied().find(|&id| {
+            matches!(
+                &self.builder.sections.get(id).data,
+                SectionData::Data(existing) if existing[..] == candidate[..]
+            )
+        })
+    }
+
     fn write(self, writer: &mut BufWriter<File>) -> Result<()> {
         let mut buffer = object::write::StreamingBuffer::new(writer);
 
@@ -74,17 +241,657 @@ impl<'data> SystemImage<'data> {
             .map_err(|e| anyhow!("Failed to write system image: {e}"))
     }
 
-    fn add_app_image<'a, R: ReadRef<'a>>(&mut self, app_bytes: R, app_name: &String) -> Result<()> {
+    /// Lower the loadable segments into `(p_paddr, bytes)` blocks in ascending
+    /// physical-address order, the common input for every non-ELF format.
+    ///
+    /// Each block is materialized at its segment's physical base; `gap_fill`
+    /// initializes the buffer so inter-section gaps and `UninitializedData`
+    /// regions take that value.
+    fn loadable_blocks(&self, gap_fill: u8) -> Result<Vec<(u64, Vec<u8>)>> {
+        let mut segments: Vec<_> = self
+            .builder
+            .segments
+            .iter()
+            .filter(|s| s.is_load() && !s.sections.is_empty())
+            .collect();
+        segments.sort_by_key(|s| s.p_paddr);
+
+        let mut blocks = Vec::with_capacity(segments.len());
+        for segment in segments {
+            // Span the segment's alloc sections in virtual-address terms so the
+            // buffer covers exactly the loaded bytes.
+            let mut lo = u64::MAX;
+            let mut hi = 0u64;
+            for id in &segment.sections {
+                let section = self.builder.sections.get(*id);
+                lo = lo.min(section.sh_addr);
+                hi = hi.max(section.sh_addr + section.sh_size);
+            }
+            if lo >= hi {
+                continue;
+            }
+
+            let size = usize::try_from(hi - lo).context("Segment too large to lower")?;
+            let mut buffer = vec![gap_fill; size];
+            for id in &segment.sections {
+                let section = self.builder.sections.get(*id);
+                if let SectionData::Data(data) = &section.data {
+                    let offset = usize::try_from(section.sh_addr - lo)
+                        .context("Section offset out of range")?;
+                    buffer[offset..offset + data.len()].copy_from_slice(data);
+                }
+                // UninitializedData (.bss) keeps the gap-fill value.
+            }
+
+            let paddr = segment.p_paddr + (lo - segment.p_vaddr);
+            blocks.push((paddr, buffer));
+        }
+        Ok(blocks)
+    }
+
+    /// Emit a flat binary: blocks concatenated at their physical addresses with
+    /// `gap_fill` between them and optional padding up to `pad_to`.
+    fn write_binary(
+        &self,
+        writer: &mut impl std::io::Write,
+        gap_fill: u8,
+        pad_to: Option<u64>,
+    ) -> Result<()> {
+        let blocks = self.loadable_blocks(gap_fill)?;
+        let Some(base) = blocks.first().map(|(addr, _)| *addr) else {
+            return Ok(());
+        };
+
+        let mut cursor = base;
+        for (addr, bytes) in &blocks {
+            if *addr < cursor {
+                bail!("Overlapping segments at {addr:#x} while writing binary");
+            }
+            for _ in cursor..*addr {
+                writer.write_all(&[gap_fill])?;
+            }
+            writer.write_all(bytes)?;
+            cursor = addr + bytes.len() as u64;
+        }
+
+        if let Some(end) = pad_to {
+            for _ in cursor..end {
+                writer.write_all(&[gap_fill])?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Emit Intel HEX records (data, type-04 extended linear address, EOF).
+    fn write_ihex(&self, writer: &mut impl std::io::Write, gap_fill: u8) -> Result<()> {
+        const CHUNK: usize = 16;
+        let mut upper: u16 = 0;
+        let mut wrote_upper = false;
+
+        for (base, bytes) in self.loadable_blocks(gap_fill)? {
+            for (i, chunk) in bytes.chunks(CHUNK).enumerate() {
+                let addr = base + (i * CHUNK) as u64;
+                let next_upper = u16::try_from(addr >> 16).context("Address exceeds 32 bits")?;
+                if !wrote_upper || next_upper != upper {
+                    upper = next_upper;
+                    wrote_upper = true;
+                    let payload = upper.to_be_bytes();
+                    Self::write_ihex_record(writer, 0x04, 0, &payload)?;
+                }
+                #[allow(clippy::cast_possible_truncation)]
+                Self::write_ihex_record(writer, 0x00, addr as u16, chunk)?;
+            }
+        }
+        Self::write_ihex_record(writer, 0x01, 0, &[])
+    }
+
+    fn write_ihex_record(
+        writer: &mut impl std::io::Write,
+        record_type: u8,
+        addr: u16,
+        data: &[u8],
+    ) -> Result<()> {
+        let len = u8::try_from(data.len()).context("Record too long")?;
+        let mut checksum = len
+            .wrapping_add((addr >> 8) as u8)
+            .wrapping_add(addr as u8)
+            .wrapping_add(record_type);
+        let mut line = format!(":{len:02X}{addr:04X}{record_type:02X}");
+        for byte in data {
+            line.push_str(&format!("{byte:02X}"));
+            checksum = checksum.wrapping_add(*byte);
+        }
+        checksum = checksum.wrapping_neg();
+        line.push_str(&format!("{checksum:02X}\n"));
+        writer.write_all(line.as_bytes()).map_err(Into::into)
+    }
+
+    /// Emit Motorola S-records with 32-bit addresses (S3 data, S7 terminator).
+    fn write_srec(&self, writer: &mut impl std::io::Write, gap_fill: u8) -> Result<()> {
+        const CHUNK: usize = 16;
+        for (base, bytes) in self.loadable_blocks(gap_fill)? {
+            for (i, chunk) in bytes.chunks(CHUNK).enumerate() {
+                let addr = u32::try_from(base + (i * CHUNK) as u64)
+                    .context("S3 address exceeds 32 bits")?;
+                Self::write_srec_record(writer, '3', addr, chunk)?;
+            }
+        }
+        // S7: 32-bit start-address termination (entry point unused here).
+        Self::write_srec_record(writer, '7', 0, &[])
+    }
+
+    fn write_srec_record(
+        writer: &mut impl std::io::Write,
+        kind: char,
+        addr: u32,
+        data: &[u8],
+    ) -> Result<()> {
+        // Count = address bytes (4) + data + checksum byte.
+        let count = u8::try_from(4 + data.len() + 1).context("S-record too long")?;
+        let mut checksum = count;
+        for byte in addr.to_be_bytes() {
+            checksum = checksum.wrapping_add(byte);
+        }
+        let mut line = format!("S{kind}{count:02X}{addr:08X}");
+        for byte in data {
+            line.push_str(&format!("{byte:02X}"));
+            checksum = checksum.wrapping_add(*byte);
+        }
+        checksum = !checksum;
+        line.push_str(&format!("{checksum:02X}\n"));
+        writer.write_all(line.as_bytes()).map_err(Into::into)
+    }
+
+    /// Write a linker-style map of the merged image to `path`.
+    ///
+    /// Sections are grouped by their originating app (parsed from the
+    /// `.name.app` suffix convention `add_app_sections` establishes); the
+    /// kernel's own sections land in a `[kernel]` group.  Each alloc section
+    /// lists its final address, size, flags, and owning load segment, followed
+    /// by the global symbols it contains sorted by value.  A section that is
+    /// not fully contained in exactly one load segment is flagged — that is the
+    /// address-shift failure mode documented in `add_app_segments`.
+    fn write_map(&self, path: &Path) -> Result<()> {
+        use std::collections::BTreeMap;
+
+        // Map each alloc section to the load segments that contain it.
+        let mut owning_segments: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (seg_idx, segment) in self.builder.segments.iter().enumerate() {
+            if !segment.is_load() {
+                continue;
+            }
+            for id in &segment.sections {
+                owning_segments.entry(id.index()).or_default().push(seg_idx);
+            }
+        }
+
+        // Group alloc sections by originating app, preserving address order.
+        let mut groups: BTreeMap<String, Vec<SectionId>> = BTreeMap::new();
+        for section in &self.builder.sections {
+            if !section.is_alloc() {
+                continue;
+            }
+            let app = Self::section_app(section.name.as_slice())
+                .unwrap_or_else(|| "kernel".to_string());
+            groups.entry(app).or_default().push(section.id());
+        }
+
+        let mut out = String::new();
+        out.push_str("System image link map\n=====================\n");
+        for (app, mut sections) in groups {
+            sections.sort_by_key(|id| self.builder.sections.get(*id).sh_addr);
+            out.push_str(&format!("\n[{app}]\n"));
+            for id in sections {
+                let section = self.builder.sections.get(id);
+                let start = section.sh_addr;
+                let end = start + section.sh_size;
+
+                let segment_desc = match owning_segments.get(&id.index()) {
+                    Some(segs) if segs.len() == 1 => {
+                        let segment = self.builder.segments.iter().nth(segs[0]).unwrap();
+                        format!(
+                            "seg vaddr={:#x} paddr={:#x} flags={:#x}",
+                            segment.p_vaddr, segment.p_paddr, segment.p_flags
+                        )
+                    }
+                    Some(segs) if segs.len() > 1 => {
+                        format!("!! in {} load segments", segs.len())
+                    }
+                    _ => "!! not in any load segment".to_string(),
+                };
+
+                out.push_str(&format!(
+                    "  {:<28} addr={:#010x} size={:#x} flags={:#x}  {}\n",
+                    String::from_utf8_lossy(section.name.as_slice()),
+                    start,
+                    section.sh_size,
+                    section.sh_flags,
+                    segment_desc,
+                ));
+
+                // Global symbols defined in this section, sorted by value.
+                let mut symbols: Vec<_> = self
+                    .builder
+                    .symbols
+                    .iter()
+                    .filter(|s| s.section == Some(id) && s.st_bind() == elf::STB_GLOBAL)
+                    .collect();
+                symbols.sort_by_key(|s| s.st_value);
+                for symbol in symbols {
+                    if symbol.st_value < start || symbol.st_value >= end.max(start + 1) {
+                        // Still list it, but note the containment mismatch.
+                        out.push_str(&format!(
+                            "      {:#010x} {}  !! outside section\n",
+                            symbol.st_value,
+                            String::from_utf8_lossy(symbol.name.as_slice()),
+                        ));
+                    } else {
+                        out.push_str(&format!(
+                            "      {:#010x} {}\n",
+                            symbol.st_value,
+                            String::from_utf8_lossy(symbol.name.as_slice()),
+                        ));
+                    }
+                }
+            }
+        }
+
+        fs::write(path, out).map_err(|e| anyhow!("Failed to write link map: {e}"))
+    }
+
+    /// Decompress every `SHF_COMPRESSED` section into plain data so the normal
+    /// rename/remap path sees uncompressed bytes, unless `keep` is set, in which
+    /// case the compressed payload and its `Elf_Chdr` header are left intact.
+    fn decompress_sections(&mut self, keep: bool) -> Result<()> {
+        if keep {
+            return Ok(());
+        }
+        let is_64 = self.builder.is_64;
+        let little_endian = self.builder.endian.is_little_endian();
+
+        for section in &mut self.builder.sections {
+            if section.sh_flags & u64::from(elf::SHF_COMPRESSED) == 0 {
+                continue;
+            }
+            let SectionData::Data(data) = &section.data else {
+                continue;
+            };
+
+            let (raw_size, body) = parse_chdr(data, is_64, little_endian)?;
+            let decompressed = inflate_zlib(body, raw_size)?;
+
+            section.sh_size = decompressed.len() as u64;
+            section.sh_flags &= !u64::from(elf::SHF_COMPRESSED);
+            section.data = SectionData::Data(Bytes::from(decompressed));
+        }
+        Ok(())
+    }
+
+    /// Compress the merged image's large `.debug_*` sections with `algo`,
+    /// prepending the appropriate `Elf_Chdr` and setting `SHF_COMPRESSED`.
+    fn compress_debug_sections(&mut self, algo: CompressDebug) -> Result<()> {
+        if algo == CompressDebug::None {
+            return Ok(());
+        }
+        // Only worth it for sizeable, not-yet-compressed debug sections.
+        const MIN_COMPRESS_BYTES: usize = 512;
+        let is_64 = self.builder.is_64;
+        let little_endian = self.builder.endian.is_little_endian();
+
+        for section in &mut self.builder.sections {
+            if section.is_alloc() || !section.name.starts_with(b".debug_") {
+                continue;
+            }
+            if section.sh_flags & u64::from(elf::SHF_COMPRESSED) != 0 {
+                continue;
+            }
+            let SectionData::Data(data) = &section.data else {
+                continue;
+            };
+            if data.len() < MIN_COMPRESS_BYTES {
+                continue;
+            }
+
+            let (ch_type, body) = match algo {
+                CompressDebug::Zlib => (elf::ELFCOMPRESS_ZLIB, deflate_zlib(data)),
+                CompressDebug::Zstd => (elf::ELFCOMPRESS_ZSTD, deflate_zstd(data)?),
+                CompressDebug::None => unreachable!(),
+            };
+
+            let mut out = build_chdr(ch_type, data.len() as u64, section.sh_addralign, is_64, little_endian);
+            out.extend_from_slice(&body);
+            section.sh_flags |= u64::from(elf::SHF_COMPRESSED);
+            section.data = SectionData::Data(Bytes::from(out));
+        }
+        Ok(())
+    }
+
+    /// Synthesize a `.note.gnu.build-id` section whose descriptor is a
+    /// deterministic hash over every emitted section.
+    ///
+    /// Sections are hashed in `(name, index)` order so the same inputs always
+    /// yield the same id regardless of the filesystem's section ordering,
+    /// giving the assembled image a content-addressed identity the loader and
+    /// debuggers can read.  The SHA-256 digest is truncated to `bits` (rounded
+    /// up to whole bytes).
+    fn add_build_id(&mut self, bits: usize) -> Result<()> {
+        use sha2::{Digest, Sha256};
+
+        let mut ordered: Vec<_> = self.builder.sections.iter().collect();
+        ordered.sort_by(|a, b| {
+            a.name
+                .as_slice()
+                .cmp(b.name.as_slice())
+                .then(a.id().index().cmp(&b.id().index()))
+        });
+
+        let mut hasher = Sha256::new();
+        for section in ordered {
+            hasher.update((section.name.len() as u64).to_le_bytes());
+            hasher.update(section.name.as_slice());
+            if let SectionData::Data(data) = &section.data {
+                hasher.update((data.len() as u64).to_le_bytes());
+                hasher.update(data.as_slice());
+            }
+        }
+        let digest = hasher.finalize();
+
+        let id_len = bits.div_ceil(8).min(digest.len());
+        let id = &digest[..id_len];
+
+        // GNU build-id note: name "GNU\0", type NT_GNU_BUILD_ID, desc = id.
+        let little_endian = self.builder.endian.is_little_endian();
+        let word = |v: u32| if little_endian { v.to_le_bytes() } else { v.to_be_bytes() };
+        let mut note = Vec::new();
+        note.extend_from_slice(&word(4)); // namesz ("GNU\0")
+        note.extend_from_slice(&word(id_len as u32)); // descsz
+        note.extend_from_slice(&word(elf::NT_GNU_BUILD_ID));
+        note.extend_from_slice(b"GNU\0");
+        note.extend_from_slice(id);
+        while note.len() % 4 != 0 {
+            note.push(0);
+        }
+
+        let section = self.builder.sections.add();
+        section.name = ByteString::from(b".note.gnu.build-id".to_vec());
+        section.sh_type = elf::SHT_NOTE;
+        section.sh_flags = u64::from(elf::SHF_ALLOC);
+        section.sh_addralign = 4;
+        section.sh_size = note.len() as u64;
+        section.data = SectionData::Data(Bytes::from(note));
+        Ok(())
+    }
+
+    /// Garbage-collect alloc sections unreachable from the entry points.
+    ///
+    /// Roots are: any section defining a global symbol named in `entries` or
+    /// matching the default `_start` entry convention, plus KEEP-like sections
+    /// (init/fini arrays and vector tables) that linkers never discard.  Edges
+    /// come from relocations: a section points at another when one of its
+    /// relocations references a symbol defined in the target.  Reachable
+    /// sections are marked transitively; unmarked alloc sections are detached
+    /// from their load segments so they are neither allocated nor emitted.
+    fn gc_sections(&mut self, entries: &[String]) -> Result<()> {
+        // symbol index -> defining section index
+        let mut symbol_section: HashMap<usize, usize> = HashMap::new();
+        for symbol in &self.builder.symbols {
+            if let Some(section) = symbol.section {
+                symbol_section.insert(symbol.id().index(), section.index());
+            }
+        }
+
+        // Build the section reference graph from relocation sections.
+        let mut edges: HashMap<usize, Vec<usize>> = HashMap::new();
+        for section in &self.builder.sections {
+            let SectionData::Relocation(relocs) = &section.data else {
+                continue;
+            };
+            let Some(from) = section.sh_info_section else {
+                continue;
+            };
+            let targets = edges.entry(from.index()).or_default();
+            for reloc in relocs {
+                if let Some(sym) = reloc.symbol {
+                    if let Some(&to) = symbol_section.get(&sym.index()) {
+                        targets.push(to);
+                    }
+                }
+            }
+        }
+
+        // Seed roots.
+        let mut worklist: Vec<usize> = Vec::new();
+        for symbol in &self.builder.symbols {
+            if symbol.st_bind() != elf::STB_GLOBAL {
+                continue;
+            }
+            let name = String::from_utf8_lossy(symbol.name.as_slice());
+            let is_root = name.starts_with("_start")
+                || entries.iter().any(|e| e.as_str() == name.as_ref());
+            if is_root {
+                if let Some(section) = symbol.section {
+                    worklist.push(section.index());
+                }
+            }
+        }
+        for section in &self.builder.sections {
+            if section.is_alloc() && Self::is_keep_section(section.name.as_slice()) {
+                worklist.push(section.id().index());
+            }
+        }
+
+        // Mark reachable sections.
+        let mut marked: HashSet<usize> = HashSet::new();
+        while let Some(index) = worklist.pop() {
+            if !marked.insert(index) {
+                continue;
+            }
+            if let Some(targets) = edges.get(&index) {
+                worklist.extend(targets.iter().copied());
+            }
+        }
+
+        // Sweep: detach unmarked alloc sections from every load segment.
+        let dead: HashSet<usize> = self
+            .builder
+            .sections
+            .iter()
+            .filter(|s| s.is_alloc() && !marked.contains(&s.id().index()))
+            .map(|s| s.id().index())
+            .collect();
+
+        for segment in &mut self.builder.segments {
+            segment
+                .sections
+                .retain(|id| !dead.contains(&id.index()));
+        }
+
+        // Delete the dead sections themselves (and any relocation section that
+        // only patched one), so the emitted ELF actually shrinks rather than
+        // carrying the bytes of unreachable code.
+        let dead_sections: Vec<SectionId> = self
+            .builder
+            .sections
+            .iter()
+            .filter(|s| {
+                dead.contains(&s.id().index())
+                    || s.sh_info_section
+                        .is_some_and(|info| dead.contains(&info.index()))
+            })
+            .map(|s| s.id())
+            .collect();
+        for id in dead_sections {
+            self.builder.sections.get_mut(id).delete = true;
+        }
+
+        // Drop symbols defined in a dead section; their defining index is about
+        // to disappear, and nothing reachable can still refer to them.
+        let dead_symbols: Vec<SymbolId> = self
+            .builder
+            .symbols
+            .iter()
+            .filter(|s| s.section.is_some_and(|sec| dead.contains(&sec.index())))
+            .map(|s| s.id())
+            .collect();
+        for id in dead_symbols {
+            self.builder.symbols.get_mut(id).delete = true;
+        }
+
+        Ok(())
+    }
+
+    /// KEEP-like sections linkers never garbage-collect.
+    fn is_keep_section(name: &[u8]) -> bool {
+        [
+            &b".init_array"[..],
+            &b".fini_array"[..],
+            &b".vectors"[..],
+            &b".vector_table"[..],
+        ]
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+    }
+
+    /// Validate the merged layout before emitting, turning the runtime
+    /// MemManage faults documented in `add_app_segments` into build errors.
+    ///
+    /// Checks: (a) no pairwise overlap between load segments owned by distinct
+    /// apps (or between an app and the kernel), (b) every alloc section's
+    /// preserved `sh_addr` span falls inside its containing segment, and (c)
+    /// each segment's base/size satisfies the requested MPU alignment.
+    ///
+    /// The alignment policy (c) is opt-in: `--mpu-pow2` requires naturally
+    /// aligned power-of-two segments, `--mpu-align N` requires a base/size
+    /// multiple of `N`.  Without either, the linker-assigned addresses are
+    /// trusted and only (a) and (b) run.
+    fn validate(&self, mpu_align: Option<u64>, require_pow2: bool) -> Result<()> {
+        struct SegInfo {
+            app: String,
+            vaddr_lo: u64,
+            paddr_lo: u64,
+            size: u64,
+        }
+
+        let mut segs = Vec::new();
+        for segment in &self.builder.segments {
+            if !segment.is_load() || segment.sections.is_empty() {
+                continue;
+            }
+
+            let mut lo = u64::MAX;
+            let mut hi = 0u64;
+            let mut app = "kernel".to_string();
+            for id in &segment.sections {
+                let section = self.builder.sections.get(*id);
+                lo = lo.min(section.sh_addr);
+                hi = hi.max(section.sh_addr + section.sh_size);
+                if let Some(name) = Self::section_app(section.name.as_slice()) {
+                    app = name;
+                }
+
+                // (b) the section's whole span must fall within the segment.
+                if section.sh_addr < segment.p_vaddr {
+                    bail!(
+                        "Section '{}' (app '{app}') at {:#x} precedes its segment vaddr {:#x}",
+                        String::from_utf8_lossy(section.name.as_slice()),
+                        section.sh_addr,
+                        segment.p_vaddr,
+                    );
+                }
+                let section_end = section.sh_addr + section.sh_size;
+                let segment_end = segment.p_vaddr + segment.p_memsz;
+                if section_end > segment_end {
+                    bail!(
+                        "Section '{}' (app '{app}') ends at {:#x}, past its segment end {:#x}",
+                        String::from_utf8_lossy(section.name.as_slice()),
+                        section_end,
+                        segment_end,
+                    );
+                }
+            }
+            if lo >= hi {
+                continue;
+            }
+            let size = hi - lo;
+
+            // (c) alignment, only when the caller opted in.
+            if require_pow2 && !size.is_power_of_two() {
+                bail!("App '{app}' segment size {size:#x} is not a power of two (PMSAv7)");
+            }
+            let pow2_align = if require_pow2 { size } else { 1 };
+            let align = mpu_align.unwrap_or(1).max(pow2_align);
+            if align > 1 {
+                let base = segment.p_paddr + (lo - segment.p_vaddr);
+                if base % align != 0 {
+                    bail!(
+                        "App '{app}' segment base {base:#x} not aligned to {align:#x}",
+                    );
+                }
+            }
+
+            segs.push(SegInfo {
+                app,
+                vaddr_lo: lo,
+                paddr_lo: segment.p_paddr + (lo - segment.p_vaddr),
+                size,
+            });
+        }
+
+        // (a) pairwise overlap between distinct owners.
+        for (i, a) in segs.iter().enumerate() {
+            for b in &segs[i + 1..] {
+                if a.app == b.app {
+                    continue;
+                }
+                let p_overlap = a.paddr_lo < b.paddr_lo + b.size && b.paddr_lo < a.paddr_lo + a.size;
+                let v_overlap =
+                    a.vaddr_lo < b.vaddr_lo + b.size && b.vaddr_lo < a.vaddr_lo + a.size;
+                if p_overlap || v_overlap {
+                    bail!(
+                        "Segments of '{}' and '{}' overlap (paddr {:#x}+{:#x} vs {:#x}+{:#x})",
+                        a.app, b.app, a.paddr_lo, a.size, b.paddr_lo, b.size,
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parse the originating app name from a merged section name following the
+    /// `.orig.app` suffix convention, or `None` for kernel/shared sections.
+    fn section_app(name: &[u8]) -> Option<String> {
+        let name = std::str::from_utf8(name).ok()?;
+        if name.starts_with(".pw_tokenizer.") {
+            return None;
+        }
+        let rest = name.strip_prefix('.')?;
+        let (_, app) = rest.rsplit_once('.')?;
+        Some(app.to_string())
+    }
+
+    fn add_app_image<'a, R: ReadRef<'a>>(
+        &mut self,
+        app_bytes: R,
+        app_name: &String,
+        resolve: bool,
+    ) -> Result<()> {
         let app_builder =
             Builder::read(app_bytes).map_err(|e| anyhow!("Failed to parse app image: {e}"))?;
 
         let mut section_map = HashMap::new();
-        self.add_app_sections(&app_builder, app_name, &mut section_map)
+        let mut reloc_sections = Vec::new();
+        self.add_app_sections(&app_builder, app_name, &mut section_map, &mut reloc_sections)
             .map_err(|e| anyhow!("Failed adding app sections: {e}"))?;
         self.add_app_segments(&app_builder, &section_map)
             .map_err(|e| anyhow!("Failed adding app segments: {e}"))?;
-        self.add_app_symbols(&app_builder, app_name, &section_map)
-            .map_err(|e| anyhow!("Failed adding app symbols: {e}"))
+        let symbol_map = self
+            .add_app_symbols(&app_builder, app_name, &section_map)
+            .map_err(|e| anyhow!("Failed adding app symbols: {e}"))?;
+        // Relocations must be rewritten after symbols exist so each entry's
+        // symbol reference can be repointed into the merged symbol table.
+        self.remap_relocations(&app_builder, &reloc_sections, &symbol_map, resolve)
+            .map_err(|e| anyhow!("Failed remapping app relocations: {e}"))
     }
 
     fn add_app_sections(
@@ -92,6 +899,7 @@ impl<'data> SystemImage<'data> {
         app: &Builder,
         app_name: &String,
         section_map: &mut HashMap<usize, SectionId>,
+        reloc_sections: &mut Vec<(usize, SectionId)>,
     ) -> Result<()> {
         let mut sections_for_fixup = Vec::new();
         for section in &app.sections {
@@ -114,6 +922,53 @@ impl<'data> SystemImage<'data> {
                 continue;
             }
 
+            // The `SHT_GROUP` control sections list their members by section
+            // index.  We are producing a fully linked image in which COMDAT
+            // groups have already been resolved (their members folded below), so
+            // the group sections are not carried forward — keeping them would
+            // leave those member indices dangling once the members are dropped.
+            if section.sh_type == elf::SHT_GROUP {
+                continue;
+            }
+
+            // COMDAT group members (`SHF_GROUP`) are deduplicated across apps:
+            // identical inline/template code shipped by every app that links a
+            // shared static library is emitted once.  A group's members are
+            // interchangeable across apps exactly when they are byte-identical,
+            // so we fold on verified content identity (the build API does not
+            // surface the group's signature symbol); `find_identical_section`
+            // keeps each member atomically matched to its twin, and the section
+            // map is redirected so relocations still resolve.
+            let is_comdat = section.sh_flags & u64::from(elf::SHF_GROUP) != 0;
+            if is_comdat {
+                let key = (section.name.to_vec(), Self::section_content_hash(section));
+                if let Some(bucket) = self.comdat_sections.get(&key) {
+                    if let Some(surviving) = self.find_identical_section(bucket, section) {
+                        section_map.insert(section.id().index(), surviving);
+                        continue;
+                    }
+                }
+            }
+
+            // Content-addressed dedup of non-alloc read-only data sections:
+            // byte-identical copies shipped by multiple apps are shared rather
+            // than re-emitted.  Tokenizer sections are excluded (handled by the
+            // concatenation path above) as are writable and non-`Data` sections.
+            let poolable = !is_comdat
+                && !is_tokenizer
+                && !section.is_alloc()
+                && section.sh_flags & u64::from(elf::SHF_WRITE) == 0
+                && matches!(section.data, SectionData::Data(_));
+            let pool_key = poolable.then(|| Self::section_content_hash(section));
+            if let Some(key) = pool_key {
+                if let Some(bucket) = self.section_pool.get(&key) {
+                    if let Some(canonical) = self.find_identical_section(bucket, section) {
+                        section_map.insert(section.id().index(), canonical);
+                        continue;
+                    }
+                }
+            }
+
             let new_section = self.builder.sections.add();
             section_map.insert(section.id().index(), new_section.id());
 
@@ -130,8 +985,31 @@ impl<'data> SystemImage<'data> {
                 sections_for_fixup.push(new_section.id());
             }
 
+            let new_section_id = new_section.id();
+            let is_reloc = matches!(section.data, SectionData::Relocation(_));
+
             Self::copy_section(section_map, section, new_section)?;
 
+            // Relocation sections carry app-local symbol references that must
+            // be repointed at the merged symbol table in `remap_relocations`.
+            if is_reloc {
+                reloc_sections.push((section.id().index(), new_section_id));
+            }
+
+            // Record the surviving copy of this COMDAT group member, but only
+            // if it can ever fold: folding is confirmed by byte comparison, so
+            // non-`Data` members (relocations, SHT_NOBITS) would only sit in
+            // the bucket and never match.
+            if is_comdat && matches!(section.data, SectionData::Data(_)) {
+                let key = (section.name.to_vec(), Self::section_content_hash(section));
+                self.comdat_sections.entry(key).or_default().push(new_section_id);
+            }
+
+            // Register this section as the canonical copy for its content.
+            if let Some(key) = pool_key {
+                self.section_pool.entry(key).or_default().push(new_section_id);
+            }
+
             // println!("Added app section '{:?}'", new_section);
         }
 
@@ -171,6 +1049,22 @@ impl<'data> SystemImage<'data> {
         dst.data = match &src.data {
             SectionData::Data(data) => SectionData::Data(Bytes::from(data.to_vec())),
             SectionData::UninitializedData(data) => SectionData::UninitializedData(*data),
+            SectionData::Relocation(relocs) => {
+                // Copy entries verbatim, keeping the app-local `symbol` ids.
+                // These are remapped to merged symbol ids after symbols are
+                // added (see `remap_relocations`); `r_offset` is preserved
+                // because sections keep their addresses (ARMv7-M fixup).
+                let copied = relocs
+                    .iter()
+                    .map(|r| Relocation {
+                        r_offset: r.r_offset,
+                        symbol: r.symbol,
+                        r_type: r.r_type,
+                        r_addend: r.r_addend,
+                    })
+                    .collect();
+                SectionData::Relocation(copied)
+            }
             SectionData::Attributes(data) => {
                 Self::copy_section_attributes(section_map, data).unwrap()
             }
@@ -319,10 +1213,27 @@ impl<'data> SystemImage<'data> {
         app: &Builder,
         app_name: &String,
         section_map: &HashMap<usize, SectionId>,
-    ) -> Result<()> {
+    ) -> Result<HashMap<usize, SymbolId>> {
+        let mut symbol_map = HashMap::new();
         for symbol in &app.symbols {
             // println!("Adding app symbol: {:?}", symbol);
+
+            // A global the app *defines* whose name also names a kernel-defined
+            // global is a real duplicate-definition bug: both are meant to bind
+            // to the single kernel symbol (e.g. the syscall table).  Surface it
+            // instead of papering over it with an `_appname` rename.
+            if symbol.st_bind() == elf::STB_GLOBAL
+                && symbol.section.is_some()
+                && self.kernel_globals.contains(symbol.name.as_slice())
+            {
+                bail!(
+                    "App '{app_name}' redefines kernel global symbol '{}'",
+                    symbol.name
+                );
+            }
+
             let new_symbol = self.builder.symbols.add();
+            symbol_map.insert(symbol.id().index(), new_symbol.id());
             if symbol.st_bind() == elf::STB_GLOBAL {
                 let new_name = format!("{}_{}", symbol.name, app_name);
                 new_symbol.name = new_name.into_bytes().into();
@@ -450,9 +1361,215 @@ impl<'data> SystemImage<'data> {
             new_symbol.version = symbol.version;
             new_symbol.version_hidden = symbol.version_hidden;
         }
+        Ok(symbol_map)
+    }
+
+    /// Rewrite every copied relocation section so its entries reference the
+    /// merged symbol table, and optionally resolve the entries whose target
+    /// value is now known.
+    ///
+    /// `reloc_sections` pairs each app relocation section's original index with
+    /// the id of its copy in the merged builder.  `symbol_map` maps app symbol
+    /// indices to the merged `SymbolId`s produced by `add_app_symbols`.
+    fn remap_relocations(
+        &mut self,
+        app: &Builder,
+        reloc_sections: &[(usize, SectionId)],
+        symbol_map: &HashMap<usize, SymbolId>,
+        resolve: bool,
+    ) -> Result<()> {
+        let machine = app.header.e_machine;
+
+        for &(_app_index, new_section_id) in reloc_sections {
+            // The section these relocations apply to (`sh_info`), already
+            // repointed into the merged image by the section fixup pass.
+            let target_section = self.builder.sections.get(new_section_id).sh_info_section;
+
+            let relocs = {
+                let section = self.builder.sections.get_mut(new_section_id);
+                let SectionData::Relocation(relocs) = &mut section.data else {
+                    bail!("Section {new_section_id:?} recorded as relocation is not one");
+                };
+                core::mem::take(relocs)
+            };
+
+            let mut kept = Vec::with_capacity(relocs.len());
+            // Patches collected while the reloc section is no longer borrowed,
+            // applied below so the target section's bytes can be mutated.
+            let mut patches: Vec<(u64, u64, u32)> = Vec::new();
+
+            for reloc in relocs {
+                let mapped_symbol = match reloc.symbol {
+                    Some(old) => match symbol_map.get(&old.index()) {
+                        Some(new) => Some(*new),
+                        None => bail!("No symbol mapping for relocation target {old:?}"),
+                    },
+                    None => None,
+                };
+
+                let remapped = Relocation {
+                    r_offset: reloc.r_offset,
+                    symbol: mapped_symbol,
+                    r_type: reloc.r_type,
+                    r_addend: reloc.r_addend,
+                };
+
+                // In `--resolve` mode, patch the reference in place against the
+                // symbol's final value, then drop the entry.  Everything else
+                // is preserved for a later link stage.
+                if resolve && Self::is_resolvable(app, &remapped) {
+                    let symbol = remapped.symbol.expect("is_resolvable requires a symbol");
+                    let st_value = self.builder.symbols.get(symbol).st_value;
+                    let value = st_value.wrapping_add_signed(remapped.r_addend);
+                    patches.push((remapped.r_offset, value, remapped.r_type));
+                    continue;
+                }
+                kept.push(remapped);
+            }
+
+            {
+                let section = self.builder.sections.get_mut(new_section_id);
+                if let SectionData::Relocation(relocs) = &mut section.data {
+                    *relocs = kept;
+                }
+            }
+
+            if patches.is_empty() {
+                continue;
+            }
+            let Some(target_section) = target_section else {
+                bail!("Relocation section {new_section_id:?} has no target (sh_info)");
+            };
+
+            // Resolve PC-relative forms against the target section's address.
+            let target_addr = self.builder.sections.get(target_section).sh_addr;
+            let section = self.builder.sections.get_mut(target_section);
+            let SectionData::Data(bytes) = &section.data else {
+                bail!("Relocation target {target_section:?} is not loadable data");
+            };
+            let mut buf = bytes.to_vec();
+            for (offset, value, r_type) in patches {
+                let place = target_addr.wrapping_add(offset);
+                Self::apply_relocation(machine, r_type, &mut buf, offset, value, place)?;
+            }
+            section.data = SectionData::Data(Bytes::from(buf));
+        }
         Ok(())
     }
 
+    /// Patch a resolved relocation into `buf` at `offset`.
+    ///
+    /// `value` is `S + A`; `place` is the virtual address of the patched word,
+    /// used by the PC-relative branch forms.  Encoding mirrors the ELF psABI:
+    /// ABS forms store the value directly, the branch forms store the
+    /// displacement in the instruction's immediate field.  ARMv7-M is
+    /// Thumb-only, so its branch relocations are the `THM_` forms (the A32
+    /// `R_ARM_CALL`/`R_ARM_JUMP24` never appear); the A64 branch is word-scaled.
+    fn apply_relocation(
+        machine: u16,
+        r_type: u32,
+        buf: &mut [u8],
+        offset: u64,
+        value: u64,
+        place: u64,
+    ) -> Result<()> {
+        let off = usize::try_from(offset).map_err(|_| anyhow!("Relocation offset too large"))?;
+
+        let read_u32 = |buf: &[u8], off: usize| -> Result<u32> {
+            let end = off
+                .checked_add(4)
+                .filter(|e| *e <= buf.len())
+                .ok_or_else(|| anyhow!("Relocation at {off} out of bounds"))?;
+            Ok(u32::from_le_bytes(buf[off..end].try_into().unwrap()))
+        };
+
+        let read_u16 = |buf: &[u8], off: usize| -> Result<u16> {
+            let end = off
+                .checked_add(2)
+                .filter(|e| *e <= buf.len())
+                .ok_or_else(|| anyhow!("Relocation at {off} out of bounds"))?;
+            Ok(u16::from_le_bytes(buf[off..end].try_into().unwrap()))
+        };
+
+        #[allow(clippy::cast_possible_truncation)]
+        match (machine, r_type) {
+            (elf::EM_ARM, elf::R_ARM_ABS32) => {
+                let end = off.checked_add(4).filter(|e| *e <= buf.len());
+                let end = end.ok_or_else(|| anyhow!("ABS32 at {off} out of bounds"))?;
+                buf[off..end].copy_from_slice(&(value as u32).to_le_bytes());
+            }
+            (elf::EM_ARM, elf::R_ARM_THM_CALL | elf::R_ARM_THM_JUMP24) => {
+                // Thumb-2 BL/B.W (T1/T4): a 32-bit instruction stored as two
+                // little-endian halfwords, with the branch target PC biased by
+                // +4 relative to the first halfword (Thumb read-PC offset).
+                //
+                // A Thumb code symbol carries the interworking bit (bit 0) set
+                // in its value; this is a same-state branch to the even
+                // instruction address, so clear it before taking the offset.
+                let target = value & !1;
+                let disp = target.wrapping_sub(place).wrapping_sub(4) as i64;
+                // The immediate is a signed 25-bit, halfword-aligned offset
+                // (imm24:'0'); reject anything out of range.
+                if !(-(1 << 24)..(1 << 24)).contains(&disp) {
+                    bail!("Thumb branch at {off} out of ±16 MiB range");
+                }
+                let disp = disp as u32;
+                let s = (disp >> 24) & 0x1;
+                let i1 = (disp >> 23) & 0x1;
+                let i2 = (disp >> 22) & 0x1;
+                let imm10 = (disp >> 12) & 0x3FF;
+                let imm11 = (disp >> 1) & 0x7FF;
+                // I1 = NOT(J1 EOR S) -> J1 = NOT(I1) EOR S (likewise J2).
+                let j1 = (i1 ^ 0x1) ^ s;
+                let j2 = (i2 ^ 0x1) ^ s;
+                let hw1 = read_u16(buf, off)?;
+                let hw2 = read_u16(buf, off + 2)?;
+                let hw1 = (hw1 & 0xF800) | (s as u16) << 10 | imm10 as u16;
+                let hw2 = (hw2 & 0xD000)
+                    | (j1 as u16) << 13
+                    | (j2 as u16) << 11
+                    | imm11 as u16;
+                buf[off..off + 2].copy_from_slice(&hw1.to_le_bytes());
+                buf[off + 2..off + 4].copy_from_slice(&hw2.to_le_bytes());
+            }
+            (elf::EM_AARCH64, elf::R_AARCH64_ABS64) => {
+                let end = off.checked_add(8).filter(|e| *e <= buf.len());
+                let end = end.ok_or_else(|| anyhow!("ABS64 at {off} out of bounds"))?;
+                buf[off..end].copy_from_slice(&value.to_le_bytes());
+            }
+            (elf::EM_AARCH64, elf::R_AARCH64_CALL26) => {
+                let disp = value.wrapping_sub(place) as i64 >> 2;
+                let existing = read_u32(buf, off)?;
+                let patched = (existing & 0xFC00_0000) | (disp as u32 & 0x03FF_FFFF);
+                buf[off..off + 4].copy_from_slice(&patched.to_le_bytes());
+            }
+            _ => bail!("Unsupported relocation type {r_type} for machine {machine}"),
+        }
+        Ok(())
+    }
+
+    /// Whether `reloc` is a relocation type this tool can resolve in place given
+    /// the final value of its referenced symbol.
+    ///
+    /// Only absolute and call/branch forms on the supported ISAs are handled;
+    /// everything else is preserved for a later link stage.
+    fn is_resolvable(app: &Builder, reloc: &Relocation) -> bool {
+        if reloc.symbol.is_none() {
+            return false;
+        }
+        match app.header.e_machine {
+            elf::EM_ARM => matches!(
+                reloc.r_type,
+                elf::R_ARM_ABS32 | elf::R_ARM_THM_CALL | elf::R_ARM_THM_JUMP24
+            ),
+            elf::EM_AARCH64 => matches!(
+                reloc.r_type,
+                elf::R_AARCH64_ABS64 | elf::R_AARCH64_CALL26
+            ),
+            _ => false,
+        }
+    }
+
     fn get_mapped_section_id(
         section_map: &HashMap<usize, SectionId>,
         id: SectionId,
@@ -534,6 +1651,91 @@ impl<'data> SystemImage<'data> {
     }
 }
 
+/// Parse an `Elf_Chdr` compression header, returning the uncompressed size and
+/// the compressed body that follows it.
+fn parse_chdr(data: &[u8], is_64: bool, little_endian: bool) -> Result<(usize, &[u8])> {
+    let read_u32 = |b: &[u8]| {
+        let a = [b[0], b[1], b[2], b[3]];
+        if little_endian { u32::from_le_bytes(a) } else { u32::from_be_bytes(a) }
+    };
+    let read_u64 = |b: &[u8]| {
+        let mut a = [0u8; 8];
+        a.copy_from_slice(&b[..8]);
+        if little_endian { u64::from_le_bytes(a) } else { u64::from_be_bytes(a) }
+    };
+
+    if is_64 {
+        if data.len() < 24 {
+            bail!("Truncated Elf64_Chdr");
+        }
+        // ch_type(4) ch_reserved(4) ch_size(8) ch_addralign(8)
+        let size = usize::try_from(read_u64(&data[8..16])).context("ch_size too large")?;
+        Ok((size, &data[24..]))
+    } else {
+        if data.len() < 12 {
+            bail!("Truncated Elf32_Chdr");
+        }
+        // ch_type(4) ch_size(4) ch_addralign(4)
+        let size = read_u32(&data[4..8]) as usize;
+        Ok((size, &data[12..]))
+    }
+}
+
+/// Build an `Elf_Chdr` header for a freshly-compressed section.
+fn build_chdr(ch_type: u32, raw_size: u64, addralign: u64, is_64: bool, little_endian: bool) -> Vec<u8> {
+    let u32_bytes = |v: u32| if little_endian { v.to_le_bytes() } else { v.to_be_bytes() };
+    let u64_bytes = |v: u64| if little_endian { v.to_le_bytes() } else { v.to_be_bytes() };
+
+    let mut out = Vec::new();
+    out.extend_from_slice(&u32_bytes(ch_type));
+    if is_64 {
+        out.extend_from_slice(&u32_bytes(0)); // ch_reserved
+        out.extend_from_slice(&u64_bytes(raw_size));
+        out.extend_from_slice(&u64_bytes(addralign));
+    } else {
+        #[allow(clippy::cast_possible_truncation)]
+        out.extend_from_slice(&u32_bytes(raw_size as u32));
+        #[allow(clippy::cast_possible_truncation)]
+        out.extend_from_slice(&u32_bytes(addralign as u32));
+    }
+    out
+}
+
+/// Inflate a zlib stream, checking the result against the expected size.
+fn inflate_zlib(body: &[u8], expected: usize) -> Result<Vec<u8>> {
+    use std::io::Read as _;
+    let mut out = Vec::with_capacity(expected);
+    flate2::read::ZlibDecoder::new(body)
+        .read_to_end(&mut out)
+        .context("zlib decompression failed")?;
+    if out.len() != expected {
+        bail!("Decompressed size {} != ch_size {expected}", out.len());
+    }
+    Ok(out)
+}
+
+/// Deflate bytes into a zlib stream.
+fn deflate_zlib(data: &[u8]) -> Vec<u8> {
+    use std::io::Write as _;
+    let mut encoder =
+        flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data).expect("in-memory write");
+    encoder.finish().expect("in-memory finish")
+}
+
+/// Compress bytes into a zstd stream.
+fn deflate_zstd(data: &[u8]) -> Result<Vec<u8>> {
+    zstd::stream::encode_all(data, 0).context("zstd compression failed")
+}
+
+/// Sanitize `name` so it is a valid ELF symbol component, replacing any
+/// character that is not alphanumeric or `_` with `_`.
+fn sanitize_symbol(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
+        .collect()
+}
+
 fn get_app_name(path: &Path, index: usize) -> Result<String> {
     let filename = path
         .file_stem()
@@ -546,43 +1748,229 @@ fn get_app_name(path: &Path, index: usize) -> Result<String> {
     // Replace any invalid characters with `_`.
     // There is no concern over name collisions, as
     // we also add a unique index suffix
-    let mut valid_name = String::new();
-    let chars = filename.chars();
-    for char in chars {
-        if char.is_alphanumeric() || char == '_' {
-            valid_name.push(char);
-        } else {
-            valid_name.push('_');
-        }
-    }
+    let mut valid_name = sanitize_symbol(&filename);
     valid_name.push_str(format!("_{index}").as_str());
 
     Ok(valid_name)
 }
 
+/// Expand the positional `--app` arguments and any `--manifest` entries into an
+/// ordered list of `(path, explicit_name)` inputs.  Manifest `path`s may be
+/// globs; explicit names are sanitized against the ELF-symbol character rules.
+fn resolve_app_inputs(args: &Args) -> Result<Vec<(PathBuf, Option<String>)>> {
+    let mut inputs: Vec<(PathBuf, Option<String>)> =
+        args.apps.iter().map(|p| (p.clone(), None)).collect();
+
+    if let Some(manifest_path) = &args.manifest {
+        let text = fs::read_to_string(manifest_path)
+            .map_err(|e| anyhow!("Failed to read manifest: {e}"))?;
+        let manifest: Manifest =
+            serde_json::from_str(&text).map_err(|e| anyhow!("Invalid manifest: {e}"))?;
+        for app in manifest.apps {
+            let name = app.name.as_deref().map(sanitize_symbol);
+            let mut matched = false;
+            for entry in glob::glob(&app.path)
+                .map_err(|e| anyhow!("Invalid glob '{}': {e}", app.path))?
+            {
+                let path = entry.map_err(|e| anyhow!("Glob error: {e}"))?;
+                inputs.push((path, name.clone()));
+                matched = true;
+            }
+            if !matched {
+                bail!("Manifest app pattern '{}' matched no files", app.path);
+            }
+        }
+    }
+
+    Ok(inputs)
+}
+
+/// A read-only view of an input file, backed by a memory map when possible and
+/// a heap buffer otherwise.  Only sections actually copied into the output
+/// allocate; the rest is borrowed directly from the mapping.
+enum InputBytes {
+    Mapped(memmap2::Mmap),
+    Heap(Vec<u8>),
+}
+
+impl std::ops::Deref for InputBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            InputBytes::Mapped(map) => map,
+            InputBytes::Heap(buf) => buf,
+        }
+    }
+}
+
+/// Memory-map `path`, falling back to a full read when mmap is unavailable
+/// (non-regular files, unsupported platforms).
+fn map_input(path: &Path, what: &str) -> Result<InputBytes> {
+    let file = File::open(path).map_err(|e| anyhow!("Failed to open {what}: {e}"))?;
+    // SAFETY: build inputs are not expected to be mutated concurrently; on any
+    // mapping failure we fall back to a plain read.
+    match unsafe { memmap2::Mmap::map(&file) } {
+        Ok(map) => Ok(InputBytes::Mapped(map)),
+        Err(_) => Ok(InputBytes::Heap(
+            fs::read(path).map_err(|e| anyhow!("Failed to read {what}: {e}"))?,
+        )),
+    }
+}
+
+/// Content hash of `bytes` as a lowercase hex SHA-256 string.
+fn hash_bytes(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(bytes);
+    let mut out = String::with_capacity(64);
+    for byte in digest {
+        out.push_str(&format!("{byte:02x}"));
+    }
+    out
+}
+
+/// Path of the sidecar build-cache manifest for a given output.
+fn manifest_path(output: &Path) -> PathBuf {
+    let mut name = output.as_os_str().to_os_string();
+    name.push(".manifest");
+    PathBuf::from(name)
+}
+
+/// Render the input portion of the manifest: the tool version, the output-
+/// affecting options, and one line per input recording its derived name/index,
+/// content hash, and path.  The image hash is appended separately so this
+/// string doubles as the cache key.
+fn manifest_inputs(
+    kernel_hash: &str,
+    options: &str,
+    apps: &[(String, usize, String, PathBuf)],
+) -> String {
+    let mut text = format!("version {}\n", env!("CARGO_PKG_VERSION"));
+    text.push_str(options);
+    text.push_str(&format!("kernel {kernel_hash}\n"));
+    for (name, index, hash, path) in apps {
+        text.push_str(&format!("app {index} {name} {hash} {}\n", path.display()));
+    }
+    text
+}
+
+/// Render the options that change the produced image, so the cache is not
+/// reused across invocations that differ only in flags (same inputs, different
+/// output).  Validation-only flags (`mpu_align`) and flags that write a
+/// side-artifact rather than the image (`map`) are deliberately omitted.
+fn manifest_options(args: &Args) -> String {
+    let mut text = format!(
+        "options format={:?} resolve={} gc={} keep_compressed={} compress_debug={:?} \
+         build_id={} build_id_bits={} gap_fill={} pad_to={:?}\n",
+        args.output_format,
+        args.resolve,
+        args.gc_sections,
+        args.keep_compressed,
+        args.compress_debug,
+        args.build_id,
+        args.build_id_bits,
+        args.gap_fill,
+        args.pad_to,
+    );
+    // `entries` only affects the output when `--gc-sections` is in effect.
+    if args.gc_sections {
+        for entry in &args.entries {
+            text.push_str(&format!("entry {entry}\n"));
+        }
+    }
+    text
+}
+
 fn assemble(args: Args) -> Result<()> {
-    // println!("Adding kernel image: {}", args.kernel.display());
-    let kernel_bytes =
-        fs::read(&args.kernel).map_err(|e| anyhow!("Failed to read kernel image: {e}"))?;
+    // Map every input up front so we can content-hash it for the build cache
+    // without materializing each ELF on the heap.
+    let kernel_bytes = map_input(&args.kernel, "kernel image")?;
+    let kernel_hash = hash_bytes(&kernel_bytes);
+
+    let resolved = resolve_app_inputs(&args)?;
+    let mut app_inputs = Vec::with_capacity(resolved.len());
+    for (index, (path, explicit_name)) in resolved.iter().enumerate() {
+        let app_bytes = map_input(path, "app image")?;
+        let app_name = match explicit_name {
+            Some(name) => name.clone(),
+            None => get_app_name(path, index)?,
+        };
+        let app_hash = hash_bytes(&app_bytes);
+        app_inputs.push((app_name, index, app_hash, path.clone(), app_bytes));
+    }
+
+    // Build cache: if every input hash and the tool version match the sidecar
+    // manifest and the output still exists, there is nothing to do.
+    let manifest = manifest_path(&args.output);
+    let key_inputs: Vec<_> = app_inputs
+        .iter()
+        .map(|(name, index, hash, path, _)| (name.clone(), *index, hash.clone(), path.clone()))
+        .collect();
+    let current_inputs = manifest_inputs(&kernel_hash, &manifest_options(&args), &key_inputs);
+    if !args.force && args.output.exists() {
+        if let Ok(existing) = fs::read_to_string(&manifest) {
+            let stored_inputs: String = existing
+                .lines()
+                .take_while(|l| !l.starts_with("image "))
+                .map(|l| format!("{l}\n"))
+                .collect();
+            if stored_inputs == current_inputs {
+                // Inputs unchanged; keep the existing output.
+                return Ok(());
+            }
+        }
+    }
 
     let mut system_image: SystemImage<'_> = SystemImage::new(&*kernel_bytes)?;
 
-    for (index, app) in args.apps.iter().enumerate() {
-        // println!("Adding app image: {}", app.display());
-        let app_bytes = fs::read(app).map_err(|e| anyhow!("Failed to read app image: {e}"))?;
+    for (app_name, _index, _hash, _path, app_bytes) in &app_inputs {
+        // println!("Adding app image: {}", _path.display());
+        system_image.add_app_image(&**app_bytes, app_name, args.resolve)?;
+    }
+
+    system_image.decompress_sections(args.keep_compressed)?;
+
+    if args.gc_sections {
+        system_image.gc_sections(&args.entries)?;
+    }
+
+    system_image.validate(args.mpu_align, args.mpu_pow2)?;
+    system_image.compress_debug_sections(args.compress_debug)?;
+
+    if args.build_id {
+        system_image.add_build_id(args.build_id_bits)?;
+    }
 
-        let app_name = get_app_name(app, index)?;
-        system_image.add_app_image(&*app_bytes, &app_name)?;
+    if let Some(map_path) = &args.map {
+        system_image.write_map(map_path)?;
     }
 
     // println!("Writing system image: {}", args.output.display());
     let mut open_options = fs::OpenOptions::new();
     open_options.write(true).create(true).truncate(true);
     let system_file = open_options
-        .open(args.output)
+        .open(&args.output)
         .map_err(|e| anyhow!("Failed to create system image: {e}"))?;
     let mut writer = BufWriter::new(system_file);
-    system_image.write(&mut writer)
+
+    match args.output_format {
+        OutputFormat::Elf => system_image.write(&mut writer),
+        OutputFormat::Bin => system_image.write_binary(&mut writer, args.gap_fill, args.pad_to),
+        OutputFormat::Ihex => system_image.write_ihex(&mut writer, args.gap_fill),
+        OutputFormat::Srec => system_image.write_srec(&mut writer, args.gap_fill),
+    }
+    .and_then(|()| writer.flush().map_err(Into::into))?;
+    drop(writer);
+
+    // Record the build-cache manifest keyed on the input hashes and stamped
+    // with the produced image's hash.
+    let image_hash = hash_bytes(&fs::read(&args.output)?);
+    let mut manifest_text = current_inputs;
+    manifest_text.push_str(&format!("image {image_hash}\n"));
+    fs::write(&manifest, manifest_text)
+        .map_err(|e| anyhow!("Failed to write build manifest: {e}"))?;
+
+    Ok(())
 }
 
 fn main() -> Result<()> {
@@ -0,0 +1,149 @@
+// Copyright 2025 The Pigweed Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not
+// use this file except in compliance with the License. You may obtain a copy of
+// the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations under
+// the License.
+
+//! Data-driven integration tests for the system assembler.
+//!
+//! Each case under `tests/fixtures/<name>/` supplies a `kernel.elf`, one or
+//! more `app*.elf` inputs, and an `expected/` directory describing the merged
+//! result.  The harness runs the assembler into a temp file, re-parses the
+//! output, and asserts against the expectations, reporting the fixture name on
+//! failure.  Discovery is by glob — like the html5lib external suites — and a
+//! missing or empty checkout is a hard failure so it cannot pass silently.
+//!
+//! `expected/` files (all optional except where noted):
+//!   * `tokenizer.bin`   — exact bytes of the merged `.pw_tokenizer.entries`.
+//!   * `symbols.txt`     — generated app symbol names, one per line, that must
+//!                         all be present in the merged symbol table.
+//! `tests/fixtures/UNSUPPORTED` lists case names to skip, one per line.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use object::{Object, ObjectSection, ObjectSymbol};
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+}
+
+fn skip_list(dir: &Path) -> BTreeSet<String> {
+    fs::read_to_string(dir.join("UNSUPPORTED"))
+        .map(|text| {
+            text.lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn case_dirs(dir: &Path) -> Vec<PathBuf> {
+    let mut cases: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("No fixtures directory at {}: {e}", dir.display()))
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.is_dir())
+        .collect();
+    cases.sort();
+    cases
+}
+
+fn run_case(case: &Path) {
+    let name = case.file_name().unwrap().to_string_lossy().to_string();
+
+    let kernel = case.join("kernel.elf");
+    assert!(kernel.exists(), "[{name}] missing kernel.elf");
+
+    let mut apps: Vec<PathBuf> = fs::read_dir(case)
+        .unwrap()
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            let file = p.file_name().unwrap().to_string_lossy();
+            file.starts_with("app") && file.ends_with(".elf")
+        })
+        .collect();
+    apps.sort();
+    assert!(!apps.is_empty(), "[{name}] no app*.elf inputs");
+
+    let output = case.join("merged.out.elf");
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_system_assembler"));
+    cmd.arg("--kernel").arg(&kernel);
+    for app in &apps {
+        cmd.arg("--app").arg(app);
+    }
+    cmd.arg("--output").arg(&output);
+    let status = cmd.status().expect("failed to spawn assembler");
+    assert!(status.success(), "[{name}] assembler exited with {status}");
+
+    let bytes = fs::read(&output).unwrap();
+    let merged = object::File::parse(&*bytes)
+        .unwrap_or_else(|e| panic!("[{name}] output is not a valid ELF: {e}"));
+
+    // Merged tokenizer section bytes.
+    let expected_tokenizer = case.join("expected").join("tokenizer.bin");
+    if expected_tokenizer.exists() {
+        let want = fs::read(&expected_tokenizer).unwrap();
+        let got = merged
+            .section_by_name(".pw_tokenizer.entries")
+            .unwrap_or_else(|| panic!("[{name}] merged image has no tokenizer section"))
+            .data()
+            .unwrap()
+            .to_vec();
+        assert_eq!(got, want, "[{name}] tokenizer bytes mismatch");
+    }
+
+    // Generated app symbol names.
+    let expected_symbols = case.join("expected").join("symbols.txt");
+    if expected_symbols.exists() {
+        let present: BTreeSet<String> = merged
+            .symbols()
+            .filter_map(|s| s.name().ok().map(String::from))
+            .collect();
+        for want in fs::read_to_string(&expected_symbols).unwrap().lines() {
+            let want = want.trim();
+            if want.is_empty() {
+                continue;
+            }
+            assert!(
+                present.contains(want),
+                "[{name}] expected symbol '{want}' missing from merged image",
+            );
+        }
+    }
+
+    let _ = fs::remove_file(&output);
+}
+
+#[test]
+fn fixtures() {
+    let dir = fixtures_dir();
+    let skip = skip_list(&dir);
+    let cases = case_dirs(&dir);
+
+    let mut ran = 0;
+    for case in &cases {
+        let name = case.file_name().unwrap().to_string_lossy().to_string();
+        if skip.contains(&name) {
+            eprintln!("skipping unsupported fixture '{name}'");
+            continue;
+        }
+        run_case(case);
+        ran += 1;
+    }
+
+    assert!(ran > 0, "no fixtures ran under {}", dir.display());
+}
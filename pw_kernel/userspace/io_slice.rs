@@ -0,0 +1,112 @@
+// Copyright 2025 The Pigweed Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not
+// use this file except in compliance with the License. You may obtain a copy of
+// the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations under
+// the License.
+
+//! Scatter/gather buffer views for the vectored channel syscalls.
+//!
+//! The vectored `channel_*` syscalls take a list of non-contiguous buffers
+//! rather than a single slice, so the kernel can gather a response out of
+//! several word-aligned fragments (or scatter a read into them) without the
+//! caller packing a contiguous bounce buffer by hand.  These are the
+//! `no_std`, `iovec`-free analogues of `std::io::IoSlice`/`IoSliceMut`:
+//! lifetime-carrying wrappers over a borrowed slice, passed across the syscall
+//! boundary as a `(ptr, len)` pair per fragment.  They are re-exported from
+//! [`syscall`](crate::syscall) so callers write `syscall::IoSlice`.
+//!
+//! A list of these is what the `ChannelReadVectored`/`ChannelTransactVectored`/
+//! `ChannelRespondVectored` SVC entry points (see the arch syscall ABI) receive
+//! and hand to the kernel channel object's gather/scatter path.
+
+use core::marker::PhantomData;
+use core::slice;
+
+/// An immutable, borrowed buffer in a gather list handed to a vectored send.
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct IoSlice<'a> {
+    ptr: *const u8,
+    len: usize,
+    _lifetime: PhantomData<&'a [u8]>,
+}
+
+impl<'a> IoSlice<'a> {
+    /// Wrap `buf` as a single fragment of a gather list.
+    #[must_use]
+    pub const fn new(buf: &'a [u8]) -> Self {
+        Self {
+            ptr: buf.as_ptr(),
+            len: buf.len(),
+            _lifetime: PhantomData,
+        }
+    }
+
+    /// The bytes this fragment refers to.
+    #[must_use]
+    pub fn as_slice(&self) -> &'a [u8] {
+        // SAFETY: `ptr`/`len` came from the borrowed slice in `new`, whose
+        // lifetime `'a` this view carries.
+        unsafe { slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    /// Number of bytes in this fragment.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether this fragment is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// A mutable, borrowed buffer in a scatter list handed to a vectored receive.
+#[repr(C)]
+pub struct IoSliceMut<'a> {
+    ptr: *mut u8,
+    len: usize,
+    _lifetime: PhantomData<&'a mut [u8]>,
+}
+
+impl<'a> IoSliceMut<'a> {
+    /// Wrap `buf` as a single fragment of a scatter list.
+    #[must_use]
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self {
+            ptr: buf.as_mut_ptr(),
+            len: buf.len(),
+            _lifetime: PhantomData,
+        }
+    }
+
+    /// The bytes this fragment refers to.
+    #[must_use]
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        // SAFETY: `ptr`/`len` came from the exclusively borrowed slice in
+        // `new`, and the borrow of `self` reborrows it.
+        unsafe { slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+
+    /// Number of bytes in this fragment.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether this fragment is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}